@@ -0,0 +1,93 @@
+use crate::Localizer;
+use crate::error::Result;
+use crate::{flatten_root, key_not_found, parse_language_file};
+
+impl Localizer {
+    // Private Functions
+    // Only the file read is async; hits (counted and moved to the back of the LRU order) and
+    // sources go through langs_cache_manager like the sync path, and loads through push_loaded.
+    async fn langs_cache_manager_async(&mut self, _lang: &str) -> Result<()> {
+        let path = match self.supported_languages.get(_lang) {
+            Some(path) if !self.supported_languages_cache.contains_key(_lang) => path.clone(),
+            _ => {
+                self.langs_cache_manager("", _lang)?;
+                return Ok(());
+            }
+        };
+        self.stats.misses += 1;
+        let modified = tokio::fs::metadata(&path).await.and_then(|m| m.modified()).ok();
+        let data: String = tokio::fs::read_to_string(&path).await?;
+        let root: serde_json::Value = parse_language_file(_lang, &path, &data)?;
+        let lang_map = flatten_root(_lang, &root, &self.key_separator)?;
+        self.push_loaded(_lang, lang_map, modified);
+        Ok(())
+    }
+
+    // Public Functions
+    /// Like get_text_by_key, but reads cold language files with tokio::fs so the executor
+    /// thread is not blocked. Languages from a source and TTL rescans stay synchronous.
+    pub async fn get_text_by_key_async(&mut self, _key: &str, _lang: &str, fallback: &str) -> Result<String> {
+        self.rescan_if_stale()?;
+        // Answer straight from each map as it is loaded: with a small cache limit, a later load
+        // may evict an earlier candidate, and a second walk would read it again synchronously.
+        let candidates: Vec<String> = self.candidate_languages(_lang, fallback);
+        let mut found: Option<String> = None;
+        for lang in &candidates {
+            self.langs_cache_manager_async(lang).await?;
+            if let Some(text) = self.supported_languages_cache.get(lang).and_then(|lang_map| lang_map.get(_key)) {
+                found = Some(text.clone());
+                break;
+            }
+        }
+        let result = found.or_else(|| self.default_map.get(_key).cloned())
+        .ok_or_else(|| key_not_found(_key, _lang, &candidates));
+        self.or_missing_key(_key, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::Localizer;
+    use crate::error::Result;
+
+    #[tokio::test]
+    async fn async_lookup_answers_without_rereading_evicted_candidates() -> Result<()> {
+        let dir = env::temp_dir().join(format!("localizer_test_{}_async", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("de-DE.json"), r#"{"farewell": "Auf Wiedersehen"}"#)?;
+        fs::write(dir.join("en-GB.json"), r#"{"greeting": "Hello"}"#)?;
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+        localizer.set_cache_limit(1)?;
+
+        assert_eq!(localizer.get_text_by_key_async("greeting", "de-DE", "en-GB").await?, "Hello");
+        // de-DE was evicted by en-GB; a second walk would have loaded it again.
+        assert_eq!(localizer.cache_stats().misses, 2);
+        assert!(localizer.get_text_by_key_async("missing", "de-DE", "en-GB").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn async_hits_count_and_refresh_the_lru_order() -> Result<()> {
+        let dir = env::temp_dir().join(format!("localizer_test_{}_async_lru", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        for (lang, text) in [("en-GB", "Hello"), ("de-DE", "Hallo"), ("fr-FR", "Bonjour")] {
+            fs::write(dir.join(format!("{}.json", lang)), format!(r#"{{"greeting": "{}"}}"#, text))?;
+        }
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+        localizer.set_cache_limit(2)?;
+
+        localizer.get_text_by_key_async("greeting", "en-GB", "en-GB").await?;
+        localizer.get_text_by_key_async("greeting", "de-DE", "de-DE").await?;
+        assert_eq!(localizer.get_text_by_key_async("greeting", "en-GB", "en-GB").await?, "Hello");
+        localizer.get_text_by_key_async("greeting", "fr-FR", "fr-FR").await?;
+
+        assert_eq!(localizer.lru_order, ["en-GB", "fr-FR"]);
+        let stats = localizer.cache_stats();
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Localizer;
+use crate::error::{LocalizerError, Result};
+
+// Bumped whenever the layout below changes; files with another version are ignored.
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct CachedLanguage {
+    lang: String,
+    path: PathBuf,
+    // Modification time of `path` when the map was parsed, as (seconds, nanoseconds) since the epoch.
+    modified: (u64, u32),
+    map: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BinaryCache {
+    version: u32,
+    key_separator: String,
+    languages: Vec<CachedLanguage>,
+}
+
+fn epoch_time(time: SystemTime) -> (u64, u32) {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    (since_epoch.as_secs(), since_epoch.subsec_nanos())
+}
+
+fn modified_time(path: &Path) -> std::io::Result<(u64, u32)> {
+    Ok(epoch_time(fs::metadata(path)?.modified()?))
+}
+
+fn bincode_error(e: bincode::Error) -> LocalizerError {
+    LocalizerError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Failed to (de)serialize binary cache: {}", e)))
+}
+
+impl Localizer {
+    /// Writes every cached file-backed language to `path` with the modification time its file
+    /// had when the map was read, so a file edited since then is reloaded after a restart.
+    /// Languages served by a source are skipped. Returns how many languages were written.
+    pub fn save_binary_cache(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let mut languages: Vec<CachedLanguage> = Vec::new();
+        for (lang, map) in &self.supported_languages_cache {
+            let Some(file) = self.supported_languages.get(lang) else { continue };
+            let Some(modified) = self.cached_mtimes.get(lang) else { continue };
+            languages.push(CachedLanguage {
+                lang: lang.clone(),
+                path: file.clone(),
+                modified: epoch_time(*modified),
+                map: map.clone(),
+            });
+        }
+
+        let cache = BinaryCache {
+            version: FORMAT_VERSION,
+            key_separator: self.key_separator.clone(),
+            languages,
+        };
+        fs::write(path, bincode::serialize(&cache).map_err(bincode_error)?)?;
+        Ok(cache.languages.len())
+    }
+
+    /// Fills the cache from a file written by save_binary_cache. An entry is used only if its
+    /// language still maps to the same file and that file's modification time is unchanged; a
+    /// file written with another format version or key separator is ignored entirely.
+    /// Returns how many languages were loaded.
+    pub fn load_binary_cache(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let cache: BinaryCache = bincode::deserialize(&fs::read(path)?).map_err(bincode_error)?;
+        if cache.version != FORMAT_VERSION || cache.key_separator != self.key_separator {
+            return Ok(0);
+        }
+
+        let mut loaded = 0;
+        for entry in cache.languages {
+            let fresh = self.supported_languages.get(&entry.lang) == Some(&entry.path)
+                && modified_time(&entry.path).ok() == Some(entry.modified);
+            if !fresh {
+                continue;
+            }
+            let modified = UNIX_EPOCH + Duration::new(entry.modified.0, entry.modified.1);
+            self.push_loaded(&entry.lang, entry.map, Some(modified));
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::time::{Duration, SystemTime};
+
+    use crate::Localizer;
+    use crate::error::Result;
+
+    #[test]
+    fn file_edited_after_caching_is_not_restored_as_fresh() -> Result<()> {
+        let dir = env::temp_dir().join(format!("localizer_test_{}_binary_cache", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let file = dir.join("en-GB.json");
+        let cache_path = dir.join("cache.bin");
+        fs::write(&file, r#"{"greeting": "old"}"#)?;
+        File::options().write(true).open(&file)?.set_modified(SystemTime::now() - Duration::from_secs(60))?;
+
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+        assert_eq!(localizer.get_text_by_key("greeting", "en-GB", "en-GB")?, "old");
+        fs::write(&file, r#"{"greeting": "new"}"#)?;
+        localizer.save_binary_cache(&cache_path)?;
+
+        let (mut restarted, _) = Localizer::with_config(&dir, "json")?;
+        assert_eq!(restarted.load_binary_cache(&cache_path)?, 0);
+        assert_eq!(restarted.get_text_by_key("greeting", "en-GB", "en-GB")?, "new");
+
+        // A save from the fresh map is restored.
+        restarted.save_binary_cache(&cache_path)?;
+        let (mut again, _) = Localizer::with_config(&dir, "json")?;
+        assert_eq!(again.load_binary_cache(&cache_path)?, 1);
+        Ok(())
+    }
+}
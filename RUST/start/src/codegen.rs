@@ -0,0 +1,132 @@
+// Generates a Rust enum with one variant per key of a reference language file, so keys can be
+// checked at compile time. This module only depends on std and serde_json, so a build script can
+// include it directly:
+//
+//     #[path = "src/codegen.rs"]
+//     mod codegen;
+//
+//     fn main() {
+//         let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("lang_keys.rs");
+//         codegen::generate_key_enum("languages/en-GB.json", &out, "LangKey").unwrap();
+//     }
+//
+// Variant names are derived from keys deterministically:
+// - every character outside [A-Za-z0-9] splits the key into words ("menu.file-open" -> menu, file, open)
+// - each word starts with an uppercase letter ("MenuFileOpen"); the rest of the word is kept as is
+// - a name that is empty, starts with a digit or is "Self" gets a "K" prefix ("404" -> "K404")
+// - when two keys map to the same name, keys are taken in sorted order and the later ones get
+//   "2", "3", ... appended ("a.b" -> "AB", "a_b" -> "AB2")
+// Variant weights ("tip.1.weight") are get_variant metadata, not text, and get no variant.
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io::Result;
+use std::path::Path;
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+// Same flattening as the Localizer with the default separator: objects and arrays nest, nulls are skipped.
+fn collect_keys(prefix: &str, value: &serde_json::Value, out: &mut BTreeSet<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                collect_keys(&join(prefix, key), child, out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                collect_keys(&join(prefix, &index.to_string()), child, out);
+            }
+        }
+        serde_json::Value::Null => {}
+        _ => {
+            out.insert(prefix.to_string());
+        }
+    }
+}
+
+// Same rule as the Localizer's is_variant_weight, with the default separator.
+fn is_variant_weight(key: &str) -> bool {
+    let mut parts = key.rsplit('.');
+    parts.next() == Some("weight")
+        && parts.next().is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+        && parts.next().is_some()
+}
+
+fn variant_name(key: &str) -> String {
+    let mut name: String = key.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) || name == "Self" {
+        name.insert(0, 'K');
+    }
+    name
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Writes an enum named `enum_name` with a variant per key in `reference_path` (nested objects
+/// and arrays flattened with ".") plus `as_str()` and `ALL`. Returns the number of variants written.
+pub fn generate_key_enum(reference_path: impl AsRef<Path>, out_path: impl AsRef<Path>, enum_name: &str) -> Result<usize> {
+    if !is_identifier(enum_name) {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid enum name: {}", enum_name)));
+    }
+    let data: String = fs::read_to_string(reference_path.as_ref())?;
+    let root: serde_json::Value = serde_json::from_str(&data).map_err(|e| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Failed to parse JSON: {}", e))
+    })?;
+
+    let mut keys: BTreeSet<String> = BTreeSet::new();
+    collect_keys("", &root, &mut keys);
+    keys.retain(|key| !is_variant_weight(key));
+
+    let mut used: HashSet<String> = HashSet::new();
+    let variants: Vec<(String, String)> = keys.into_iter().map(|key| {
+        let base = variant_name(&key);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !used.insert(name.clone()) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        (name, key)
+    }).collect();
+
+    let mut source = format!("// @generated by generate_key_enum from {}. Do not edit.\n\n", reference_path.as_ref().display());
+    source.push_str("#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]\n");
+    source.push_str(&format!("pub enum {} {{\n", enum_name));
+    for (name, _) in &variants {
+        source.push_str(&format!("    {},\n", name));
+    }
+    source.push_str("}\n\n");
+    source.push_str(&format!("impl {} {{\n", enum_name));
+    source.push_str(&format!("    pub const ALL: &'static [{}] = &[\n", enum_name));
+    for (name, _) in &variants {
+        source.push_str(&format!("        {}::{},\n", enum_name, name));
+    }
+    source.push_str("    ];\n\n");
+    source.push_str("    pub fn as_str(&self) -> &'static str {\n        match self {\n");
+    for (name, key) in &variants {
+        source.push_str(&format!("            {}::{} => {:?},\n", enum_name, name, key));
+    }
+    source.push_str("        }\n    }\n}\n");
+
+    fs::write(out_path.as_ref(), source)?;
+    Ok(variants.len())
+}
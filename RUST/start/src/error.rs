@@ -0,0 +1,63 @@
+use std::fmt;
+use std::path::PathBuf;
+
+// Errors returned by the Localizer. Lookups that simply find nothing are LanguageNotFound,
+// KeyNotFound or KeyNotFoundInChain (see is_not_found), so they can be told apart from real IO and parse failures.
+#[derive(Debug)]
+pub enum LocalizerError {
+    LanguageNotFound(String),
+    KeyNotFound { key: String, lang: String },
+    KeyNotFoundInChain { key: String, chain: Vec<String> },
+    ParseError { lang: String, source: Box<dyn std::error::Error + Send + Sync> },
+    UnfilledPlaceholders { key: String, names: Vec<String> },
+    InvalidInput(String),
+    // Every language code several files map to, with those files (the first is the one a
+    // lenient scan uses).
+    DuplicateLanguages(Vec<(String, Vec<PathBuf>)>),
+    Io(std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, LocalizerError>;
+
+impl LocalizerError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, LocalizerError::LanguageNotFound(_) | LocalizerError::KeyNotFound { .. } | LocalizerError::KeyNotFoundInChain { .. })
+    }
+}
+
+impl fmt::Display for LocalizerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocalizerError::LanguageNotFound(lang) => write!(f, "Language not supported: {}", lang),
+            LocalizerError::KeyNotFound { key, lang } => write!(f, "Key '{}' not found in language '{}'", key, lang),
+            LocalizerError::KeyNotFoundInChain { key, chain } => write!(f, "Key '{}' not found in any of: {}", key, chain.join(" -> ")),
+            LocalizerError::ParseError { lang, source } => write!(f, "Failed to parse language '{}': {}", lang, source),
+            LocalizerError::UnfilledPlaceholders { key, names } => write!(f, "Unfilled placeholders in '{}': {}", key, names.join(", ")),
+            LocalizerError::InvalidInput(message) => write!(f, "{}", message),
+            LocalizerError::DuplicateLanguages(collisions) => {
+                let listed: Vec<String> = collisions.iter().map(|(lang, paths)| {
+                    let paths: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+                    format!("{} ({})", lang, paths.join(", "))
+                }).collect();
+                write!(f, "Several files map to the same language: {}", listed.join("; "))
+            }
+            LocalizerError::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LocalizerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LocalizerError::ParseError { source, .. } => Some(source.as_ref()),
+            LocalizerError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LocalizerError {
+    fn from(e: std::io::Error) -> LocalizerError {
+        LocalizerError::Io(e)
+    }
+}
@@ -1,256 +1,2764 @@
-use std::io::Result;
-use serde_json;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::env;
-use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime};
+use std::hash::{BuildHasher, RandomState};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+#[cfg(feature = "async")]
+mod async_loader;
+#[cfg(feature = "binary-cache")]
+mod binary_cache;
+mod codegen;
+mod error;
+mod shared;
+#[cfg(feature = "sqlite")]
+mod sqlite_source;
+#[cfg(feature = "watch")]
+mod watcher;
 
+use error::{LocalizerError, Result};
+use shared::SharedLocalizer;
+
+
+#[derive(Clone)]
 struct Config {
     runtime_cwd: PathBuf,
     language_file_dir_path: PathBuf,
-    language_file_extension: String,
+    // With the leading dot, in precedence order: when several files map to one language,
+    // the one whose extension comes first is used.
+    language_file_extensions: Vec<String>,
+}
+
+// What lookups return when neither the languages nor the default map have the key.
+// IO and parse errors are always returned as errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MissingKeyBehavior {
+    // Return a NotFound error (the default).
+    Error,
+    // Return the key verbatim. Interpolating methods do not fill placeholders in it.
+    ReturnKey,
+    // The key is the default message ("Hello, {name}"), so it is returned and
+    // interpolated exactly as a translated value would be.
+    KeyAsMessage,
+}
+
+// Escaping applied to each argument before it is substituted into a template.
+// The template itself is trusted (it comes from the translation files); only arguments are escaped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EscapeMode {
+    // Arguments are inserted as is.
+    None,
+    // & < > " ' become entities, so an argument cannot open tags or leave a quoted attribute.
+    // Not enough for unquoted attributes, URLs, or <script>/<style> contents.
+    Html,
+    // Escapes as the contents of a JSON string literal (quotes, backslashes, control characters),
+    // so an argument cannot end the string it is placed in. The template supplies the quotes.
+    Json,
+    // Wraps the argument in POSIX sh single quotes, so it stays one word with no expansion.
+    // The template must not quote the placeholder itself; not valid for cmd.exe or PowerShell.
+    ShellArg,
+}
+
+// How get_variant picks among "key.0", "key.1", ... Weights are read from "key.<n>.weight"
+// and default to 1; a variant with weight 0 is never picked by WeightedRandom unless every
+// weight is 0, in which case all variants are equally likely.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum VariantStrategy {
+    // The n-th variant, wrapping around past the last one.
+    Index(usize),
+    Random,
+    WeightedRandom,
+}
+
+// Opaque handle to a key resolved by prepare_keys. It stops resolving once its language is
+// reloaded, rescanned or otherwise replaced, and is only meaningful for the Localizer that made it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct KeyHandle {
+    slot: usize,
+    index: usize,
+    generation: u64,
+}
+
+// Values copied out of a language map at prepare time; `lang` is None once invalidated.
+// `keys` lets a repeated prepare_keys call hand back the same handles.
+struct HandleTable {
+    lang: Option<String>,
+    keys: Vec<String>,
+    generation: u64,
+    values: Vec<Option<String>>,
+}
+
+// Cache counters since construction or the last reset_stats, plus the current occupancy.
+// `evictions` counts languages dropped in LRU order, by the cache limit or trim_cache_to, not
+// reload invalidations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub cached_languages: usize,
+    pub limit: usize,
+}
+
+// Severity of a diagnostic passed to the logger (see Localizer::set_logger).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LogLevel {
+    Info,
+    Warn,
+    Error,
 }
 
+// Receives the library's diagnostics. Send + Sync so a Localizer can still be shared.
+type Logger = Box<dyn Fn(LogLevel, &str) + Send + Sync>;
+
+// Info goes to stdout, warnings and errors to stderr.
+fn default_logger() -> Logger {
+    Box::new(|level, message| match level {
+        LogLevel::Info => println!("{}", message),
+        LogLevel::Warn | LogLevel::Error => eprintln!("{}", message),
+    })
+}
+
+// (language code, every file that normalized to it); the first path is the one in use.
+type ScanCollisions = Vec<(String, Vec<PathBuf>)>;
+
+// Per language: (keys missing compared to a reference language, keys the reference lacks).
+type KeyDifferences = HashMap<String, (Vec<String>, Vec<String>)>;
+
+// Where locate_text found a text: the index of the holding language (None for the default
+// map) and which of the requested keys matched.
+type TextLocation<'k> = (Option<usize>, &'k str);
+
+// A file's (modification time, length in bytes), compared to tell whether it changed.
+type FileStamp = (SystemTime, u64);
+
 struct Localizer {
     supported_languages: HashMap<String, PathBuf>,
+    // Modification time and length of the supported_languages files at the last scan; rescans
+    // only evict languages whose file changed since. The length catches edits made within one
+    // mtime tick on coarse filesystems. The watcher updates the entries of files it reports;
+    // files registered by hand (add_language, load_language_as) have no entry here.
+    scanned_stamps: HashMap<String, FileStamp>,
+    // Modification time of each cached file-backed language's file, taken just before its map
+    // was read, so the binary cache can tell whether a saved map still matches the file.
+    cached_mtimes: HashMap<String, SystemTime>,
     supported_languages_cache: HashMap<String, HashMap<String, String>>,
     lru_order: Vec<String>,
     sup_lang_cache_limit: usize,
+    key_separator: String,
+    default_map: HashMap<String, String>,
+    source: Option<Box<dyn LanguageSource>>,
+    source_languages: HashSet<String>,
+    scan_collisions: ScanCollisions,
+    missing_key_behavior: MissingKeyBehavior,
+    // Rescan the directory on lookup once the last scan is older than the TTL. None = disabled.
+    scan_ttl: Option<(Duration, Config)>,
+    last_scan: Instant,
+    fallback_chain: Vec<String>,
+    handle_tables: Vec<HandleTable>,
+    handle_generation: u64,
+    stats: CacheStats,
+    logger: Logger,
+    #[cfg(feature = "watch")]
+    watch_state: Option<watcher::WatchState>,
 }
 
-impl Localizer {
-    // Private Functions
-    fn langs_cache_manager(&mut self, _key: &str, _lang: &str) -> Result<bool> {
-        if self.supported_languages_cache.contains_key(_lang) {
-            self.lru_order.retain(|l| l != _lang);
-            self.lru_order.push(_lang.to_string());
-            return Ok(true);
-        } else if !self.supported_languages_cache.contains_key(_lang) {
-            // Limit ignored, always add new lang.
-            // Why? -> Adding new language caches and deleting existing ones will cause the cache capacity limit to be reached. 
-            // The limit may be exceeded during this process, but the cache capacity will eventually converge exactly to the limit.
-            self.lru_order.retain(|l| l != _lang);
-            self.lru_order.push(_lang.to_string());
-            let data: String = fs::read_to_string(self.supported_languages.get(_lang)
-            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Language file not found"))?)?;
-            let lang_map: HashMap<String, String> = serde_json::from_str(&data).map_err(|e| {
-                std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Failed to parse JSON: {}", e))
+// A backend that serves languages without a file in the language directory.
+// Loaded maps go through the same cache and LRU order as file-backed languages.
+// Send + Sync so a Localizer with a source can still be shared (see SharedLocalizer).
+trait LanguageSource: Send + Sync {
+    fn languages(&self) -> Result<Vec<String>>;
+    fn load_language(&self, lang: &str) -> Result<HashMap<String, String>>;
+    // Whether reloading can pick up changes; false for data fixed at build time.
+    fn reloadable(&self) -> bool {
+        true
+    }
+}
+
+// Languages held in memory, e.g. built at runtime or received over the network.
+struct MemorySource {
+    languages: HashMap<String, HashMap<String, String>>,
+}
+
+impl MemorySource {
+    pub fn new() -> MemorySource {
+        MemorySource { languages: HashMap::new() }
+    }
+
+    pub fn with_language(mut self, lang: &str, entries: &[(&str, &str)]) -> MemorySource {
+        let lang_map: HashMap<String, String> = entries.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        self.languages.insert(lang.to_string(), lang_map);
+        self
+    }
+}
+
+impl LanguageSource for MemorySource {
+    fn languages(&self) -> Result<Vec<String>> {
+        Ok(self.languages.keys().cloned().collect())
+    }
+
+    fn load_language(&self, lang: &str) -> Result<HashMap<String, String>> {
+        self.languages.get(lang).cloned()
+        .ok_or_else(|| LocalizerError::LanguageNotFound(lang.to_string()))
+    }
+}
+
+// Languages compiled into the binary (e.g. with include_str!), parsed once at construction.
+// There is nothing behind them to reload from.
+struct EmbeddedSource {
+    languages: HashMap<String, HashMap<String, String>>,
+}
+
+impl EmbeddedSource {
+    pub fn parse(files: &[(&str, &str)], separator: &str) -> Result<EmbeddedSource> {
+        let mut languages: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for (lang, json) in files {
+            let lang = canonical_lang_code(lang);
+            let root: serde_json::Value = serde_json::from_str(json).map_err(|e| {
+                LocalizerError::ParseError { lang: lang.clone(), source: Box::new(e) }
             })?;
-            self.supported_languages_cache.insert(_lang.to_string(), lang_map);
-            if self.supported_languages_cache.len() <= self.sup_lang_cache_limit as usize {
-                return Ok(true);
-            } else {
-                let oldest_lang = self.lru_order.remove(0);
-                self.supported_languages_cache.remove(&oldest_lang);
-                return Ok(true);
+            let lang_map = flatten_root(&lang, &root, separator)?;
+            languages.insert(lang, lang_map);
+        }
+        Ok(EmbeddedSource { languages })
+    }
+}
+
+impl LanguageSource for EmbeddedSource {
+    fn languages(&self) -> Result<Vec<String>> {
+        Ok(self.languages.keys().cloned().collect())
+    }
+
+    fn load_language(&self, lang: &str) -> Result<HashMap<String, String>> {
+        self.languages.get(lang).cloned()
+        .ok_or_else(|| LocalizerError::LanguageNotFound(lang.to_string()))
+    }
+
+    fn reloadable(&self) -> bool {
+        false
+    }
+}
+
+// Nested objects are flattened into separator-joined keys, e.g. {"menu": {"open": ".."}} -> "menu.open",
+// and array items are keyed by index ("list.0", "list.1"). Numbers and bools are stored as their
+// JSON text ("3", "true"); nulls are skipped.
+fn flatten_value(prefix: &str, value: &serde_json::Value, separator: &str, out: &mut HashMap<String, String>) {
+    let join = |key: &str| if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}{}{}", prefix, separator, key)
+    };
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                flatten_value(&join(key), child, separator, out);
             }
-        } else if self.supported_languages.len() == 0 {
-            eprintln!("No supported languages found.");
-            return Ok(false);
-        } else {
-            eprintln!("Not expected situation in langs_cache_manager.");
-            eprintln!("Clearing all caches.");
-            self.supported_languages_cache.clear();
-            self.lru_order.clear();
-            return Ok(false);
         }
+        serde_json::Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                flatten_value(&join(&index.to_string()), child, separator, out);
+            }
+        }
+        serde_json::Value::String(text) => {
+            out.insert(prefix.to_string(), text.clone());
+        }
+        serde_json::Value::Number(_) | serde_json::Value::Bool(_) => {
+            out.insert(prefix.to_string(), value.to_string());
+        }
+        serde_json::Value::Null => {}
+    }
+}
+
+// A language file's root must be an object; its values are flattened into one map.
+fn flatten_root(_lang: &str, root: &serde_json::Value, separator: &str) -> Result<HashMap<String, String>> {
+    if !root.is_object() {
+        return Err(LocalizerError::ParseError { lang: _lang.to_string(), source: "Language file root must be an object".into() });
     }
+    let mut lang_map: HashMap<String, String> = HashMap::new();
+    flatten_value("", root, separator, &mut lang_map);
+    Ok(lang_map)
+}
+
+enum Segment {
+    Literal(String),
+    Placeholder(String),
+}
 
-    fn scan_languages(config: &Config) -> Result<HashMap<String, PathBuf>> {
-        let lang_files: HashMap<String, PathBuf> = fs::read_dir(&config.language_file_dir_path)?
-            .filter_map(|entry| {
-                let entry: fs::DirEntry = entry.ok()?;
-                let path = entry.path();
-                if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some(config.language_file_extension.trim_start_matches('.')) {
-                    let lang_code = path.file_stem().and_then(|s| s.to_str())?.to_string();
-                    Some((lang_code, path))
+// Splits a value into literal text and {name} placeholders. "{{" and "}}" are escaped braces.
+fn parse_segments(text: &str) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut literal = String::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '{' if chars.get(i + 1) == Some(&'{') => {
+                literal.push('{');
+                i += 2;
+            }
+            '}' if chars.get(i + 1) == Some(&'}') => {
+                literal.push('}');
+                i += 2;
+            }
+            '{' => {
+                let name: String = chars[i + 1..].iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .collect();
+                let end = i + 1 + name.chars().count();
+                if !name.is_empty() && chars.get(end) == Some(&'}') {
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(name));
+                    i = end + 1;
                 } else {
-                    None
+                    literal.push('{');
+                    i += 1;
                 }
-            })
-            .collect();
-        Ok(lang_files)
+            }
+            c => {
+                literal.push(c);
+                i += 1;
+            }
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
     }
+    segments
+}
 
-    // Public Functions
-    pub fn new() -> Result<(Localizer, Config)> {
+// Returns placeholder names in order of appearance.
+fn parse_placeholders(text: &str) -> Vec<String> {
+    parse_segments(text).into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Placeholder(name) => Some(name),
+            Segment::Literal(_) => None,
+        })
+        .collect()
+}
 
-        let ldsp = PathBuf::from("./languages/");
-        match fs::create_dir_all(&ldsp) {
-            Ok(_) => {},
-            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {},
-            Err(e) => return Err(e),
+// Placeholders without a matching argument are left as "{name}".
+fn interpolate(text: &str, args: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    for segment in parse_segments(text) {
+        match segment {
+            Segment::Literal(literal) => out.push_str(&literal),
+            Segment::Placeholder(name) => match args.get(&name) {
+                Some(value) => out.push_str(value),
+                None => {
+                    out.push('{');
+                    out.push_str(&name);
+                    out.push('}');
+                }
+            },
         }
-        let ldsp = fs::canonicalize(&ldsp)?;
+    }
+    out
+}
 
-        let config = Config {
-            runtime_cwd: env::current_dir()?,
-            language_file_dir_path: ldsp.clone(),
-            language_file_extension: String::from(".json"),
-        };
+// Distinct placeholder names in `text` that `args` has no value for, in order of appearance.
+fn unfilled_placeholders(text: &str, args: &HashMap<String, String>) -> Vec<String> {
+    let mut unfilled: Vec<String> = Vec::new();
+    for name in parse_placeholders(text) {
+        if !args.contains_key(&name) && !unfilled.contains(&name) {
+            unfilled.push(name);
+        }
+    }
+    unfilled
+}
 
-        if !config.language_file_dir_path.exists() {
-            fs::create_dir_all(&config.language_file_dir_path)?;
+fn escape_value(value: &str, mode: EscapeMode) -> String {
+    match mode {
+        EscapeMode::None => value.to_string(),
+        EscapeMode::Html => {
+            let mut out = String::with_capacity(value.len());
+            for c in value.chars() {
+                match c {
+                    '&' => out.push_str("&amp;"),
+                    '<' => out.push_str("&lt;"),
+                    '>' => out.push_str("&gt;"),
+                    '"' => out.push_str("&quot;"),
+                    '\'' => out.push_str("&#39;"),
+                    c => out.push(c),
+                }
+            }
+            out
         }
-        let _lang_files: HashMap<String, PathBuf> = Localizer::scan_languages(&config)?;
+        EscapeMode::Json => {
+            let quoted = serde_json::Value::String(value.to_string()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+        EscapeMode::ShellArg => format!("'{}'", value.replace('\'', "'\\''")),
+    }
+}
 
-        println!("Language files found: {}", _lang_files.len());
-        let localizer = Localizer {
-            supported_languages: _lang_files,
-            supported_languages_cache: HashMap::new(),
-            lru_order: Vec::new(),
-            sup_lang_cache_limit: 5,
-        };
+// Moves every key of `pending` that `lang_map` has into `texts`.
+fn take_found(lang_map: &HashMap<String, String>, pending: &mut Vec<&str>, texts: &mut HashMap<String, String>) {
+    pending.retain(|key| match lang_map.get(*key) {
+        Some(text) => {
+            texts.insert(key.to_string(), text.clone());
+            false
+        }
+        None => true,
+    });
+}
 
-        return Ok((localizer, config));
+// The keys get_texts found nowhere, each listed once.
+fn unique_missing(pending: Vec<&str>) -> Vec<String> {
+    let mut missing: Vec<String> = Vec::new();
+    for key in pending {
+        if !missing.iter().any(|m| m == key) {
+            missing.push(key.to_string());
+        }
     }
+    missing
+}
 
-    pub fn get_supported_languages(&self) -> Result<Vec<String>> {
-        Ok(self.supported_languages.iter().filter_map(
-            |(lang_code, path)| {
-                if path.exists() {
-                    Some(lang_code.clone())
-                } else {
-                    None
-                }
-            }
-        ).collect())
+// The error for a key no candidate language had: reported against the first candidate, or as
+// an unknown language when none of the requested ones is supported.
+fn key_not_found(_key: &str, _lang: &str, candidates: &[String]) -> LocalizerError {
+    match candidates.first() {
+        Some(first) => LocalizerError::KeyNotFound { key: _key.to_string(), lang: first.clone() },
+        None => LocalizerError::LanguageNotFound(_lang.to_string()),
     }
+}
 
-    pub fn get_text_by_key(&mut self, _key: &str, mut _lang: &str, fallback: &str) -> Result<String> {
-        let effective_fallback = if fallback != "" && !self.supported_languages.contains_key(_lang) {
-            fallback
-        } else {
-            "en-GB"
-        };
+// "tip.1.weight" is get_variant metadata rather than text, so it is not a key translators need
+// to provide; coverage and validation skip it.
+fn is_variant_weight(key: &str, separator: &str) -> bool {
+    let mut parts = key.rsplit(separator);
+    parts.next() == Some("weight")
+        && parts.next().is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+        && parts.next().is_some()
+}
 
-        let effective_lang = if self.supported_languages.contains_key(_lang) {
-            _lang
-        } else {
-            effective_fallback
-        };
+// The keys languages are compared on by validate_against and overall_coverage: variant
+// weights are left out, and plural forms ("items.one", "items.few") collapse to their base key,
+// since each language has its own set of CLDR categories (see plural_category).
+fn comparable_keys(lang_map: &HashMap<String, String>, separator: &str) -> HashSet<String> {
+    lang_map.keys()
+        .filter(|key| !is_variant_weight(key, separator))
+        .map(|key| match key.rsplit_once(separator) {
+            Some((base, category)) if !base.is_empty() && PLURAL_CATEGORIES.contains(&category) => base.to_string(),
+            _ => key.clone(),
+        })
+        .collect()
+}
 
-        let passing = self.langs_cache_manager(_key, effective_lang)?;
-        let lang_map = &self.supported_languages_cache.get(effective_lang)
-        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Language not found in cache"))?;
+// Not suitable for anything security-related; only used to pick flavor-text variants.
+fn random_u64() -> u64 {
+    RandomState::new().hash_one(Instant::now())
+}
 
-        if passing {
-            if lang_map.contains_key(_key) {
-                return Ok(lang_map.get(_key).ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Key not found in language map"))?.to_string());
-            } else {
-                return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Key not found in language map"));
-            } 
-        } else {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "Failed to manage language cache"));
+// read_dir and create_dir_all report a file in place of the language directory confusingly.
+fn expect_directory(path: &Path) -> Result<()> {
+    // A trailing separator ("languages/") makes a file look like a missing directory.
+    let path = path.components().as_path();
+    if path.is_file() {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotADirectory, format!("Expected a directory but found a file: {}", path.display())).into());
+    }
+    Ok(())
+}
+
+// CLDR plural category of an integer count in `lang`, decided by the primary subtag
+// ("pl-PL" -> "pl"). Only the integer rules are covered; unknown languages use the
+// English one/other rule.
+const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+fn plural_category(lang: &str, count: i64) -> &'static str {
+    let primary = lang.split(['-', '_']).next().unwrap_or("").to_ascii_lowercase();
+    let n = count.unsigned_abs();
+    let (n10, n100) = (n % 10, n % 100);
+    match primary.as_str() {
+        "ja" | "ko" | "zh" | "vi" | "th" | "id" | "ms" | "tr" => "other",
+        "fr" | "pt" => if n <= 1 { "one" } else { "other" },
+        "ru" | "uk" | "be" => {
+            if n10 == 1 && n100 != 11 { "one" }
+            else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) { "few" }
+            else { "many" }
         }
+        "pl" => {
+            if n == 1 { "one" }
+            else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) { "few" }
+            else { "many" }
+        }
+        "cs" | "sk" => match n {
+            1 => "one",
+            2..=4 => "few",
+            _ => "other",
+        },
+        "ar" => match (n, n100) {
+            (0, _) => "zero",
+            (1, _) => "one",
+            (2, _) => "two",
+            (_, 3..=10) => "few",
+            (_, 11..=99) => "many",
+            _ => "other",
+        },
+        _ => if n == 1 { "one" } else { "other" },
     }
+}
 
-    pub fn rescan_languages(&mut self, config: &Config) -> Result<bool> {
-        let _lang_files: HashMap<String, PathBuf> = Localizer::scan_languages(config)?;
-        self.supported_languages = _lang_files;
+// Extensions scanned by default, in precedence order: JSON beats YAML, which beats TOML.
+fn default_extensions() -> Vec<String> {
+    let mut extensions: Vec<String> = vec![String::from(".json")];
+    if cfg!(feature = "yaml") {
+        extensions.extend([String::from(".yaml"), String::from(".yml")]);
+    }
+    if cfg!(feature = "toml") {
+        extensions.push(String::from(".toml"));
+    }
+    extensions
+}
+
+// Position of `path`'s extension in the configured set, or None if it is not a language file.
+// Extensions match case-insensitively, so en-GB.JSON is a language file too.
+fn extension_rank(config: &Config, path: &Path) -> Option<usize> {
+    let extension = path.extension().and_then(|s| s.to_str())?;
+    config.language_file_extensions.iter().position(|e| e.trim_start_matches('.').eq_ignore_ascii_case(extension))
+}
+
+// The format is picked from the file extension; anything other than YAML or TOML is read as JSON,
+// so custom extensions keep working for JSON files.
+fn parse_language_file(_lang: &str, path: &Path, data: &str) -> Result<serde_json::Value> {
+    let parse_error = |source: Box<dyn std::error::Error + Send + Sync>| LocalizerError::ParseError { lang: _lang.to_string(), source };
+    match path.extension().and_then(|s| s.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        #[cfg(feature = "yaml")]
+        Some("yaml" | "yml") => serde_yaml::from_str(data).map_err(|e| parse_error(Box::new(e))),
+        #[cfg(not(feature = "yaml"))]
+        Some("yaml" | "yml") => Err(parse_error("YAML language files need the 'yaml' feature".into())),
+        #[cfg(feature = "toml")]
+        Some("toml") => toml::from_str(data).map_err(|e| parse_error(Box::new(e))),
+        #[cfg(not(feature = "toml"))]
+        Some("toml") => Err(parse_error("TOML language files need the 'toml' feature".into())),
+        _ => serde_json::from_str(data).map_err(|e| parse_error(Box::new(e))),
+    }
+}
 
-        for lang in self.supported_languages.keys() {
-            self.supported_languages_cache.remove(lang);
-            self.lru_order.retain(|l| l != lang);
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+// Files whose stamp cannot be read are left out, so they always count as changed.
+fn file_stamps(files: &HashMap<String, PathBuf>) -> HashMap<String, FileStamp> {
+    files.iter()
+        .filter_map(|(lang, path)| Some((lang.clone(), file_stamp(path)?)))
+        .collect()
+}
+
+fn serialize_catalog(catalog: &BTreeMap<String, String>) -> Result<String> {
+    serde_json::to_string_pretty(catalog).map_err(|e| std::io::Error::other(format!("Failed to serialize catalog: {}", e)).into())
+}
+
+// BCP-47 casing with '-' as the separator: lowercase language, titlecase script, uppercase
+// region, so en_GB.json is "en-GB" and "ZH_hant_tw" is "zh-Hant-TW". Used for file names and
+// requested tags alike.
+fn canonical_lang_code(tag: &str) -> String {
+    let subtags: Vec<String> = tag.split(['-', '_']).enumerate().map(|(i, subtag)| {
+        let is_alpha = subtag.chars().all(|c| c.is_ascii_alphabetic());
+        if i > 0 && subtag.len() == 4 && is_alpha {
+            let (first, rest) = subtag.split_at(1);
+            format!("{}{}", first.to_ascii_uppercase(), rest.to_ascii_lowercase())
+        } else if i > 0 && ((subtag.len() == 2 && is_alpha) || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))) {
+            subtag.to_ascii_uppercase()
+        } else {
+            subtag.to_ascii_lowercase()
+        }
+    }).collect();
+    subtags.join("-")
+}
+
+impl Localizer {
+    // Private Functions
+    fn from_scan(_lang_files: HashMap<String, PathBuf>, _collisions: ScanCollisions) -> Localizer {
+        Localizer {
+            scanned_stamps: file_stamps(&_lang_files),
+            supported_languages: _lang_files,
+            cached_mtimes: HashMap::new(),
+            supported_languages_cache: HashMap::new(),
+            lru_order: Vec::new(),
+            sup_lang_cache_limit: 5,
+            key_separator: String::from("."),
+            default_map: HashMap::new(),
+            source: None,
+            source_languages: HashSet::new(),
+            scan_collisions: _collisions,
+            missing_key_behavior: MissingKeyBehavior::Error,
+            scan_ttl: None,
+            last_scan: Instant::now(),
+            fallback_chain: Vec::new(),
+            handle_tables: Vec::new(),
+            handle_generation: 0,
+            stats: CacheStats::default(),
+            logger: default_logger(),
+            #[cfg(feature = "watch")]
+            watch_state: None,
         }
-        Ok(true)
     }
 
-    pub fn reload_language(&mut self, _lang: &str) -> Result<bool> {
-        if self.supported_languages.contains_key(_lang) {
-            self.supported_languages_cache.remove(_lang);
+    fn langs_cache_manager(&mut self, _key: &str, _lang: &str) -> Result<bool> {
+        if self.supported_languages_cache.contains_key(_lang) {
+            self.stats.hits += 1;
             self.lru_order.retain(|l| l != _lang);
-            
-            self.langs_cache_manager("", _lang)?;
+            self.lru_order.push(_lang.to_string());
+            Ok(true)
+        } else if !self.supported_languages_cache.contains_key(_lang) {
+            // Load before touching lru_order so a failed load leaves no orphan entry, then evict
+            // until the limit holds again. _lang is most recently used, so it is never the one evicted.
+            self.stats.misses += 1;
+            let modified = self.file_mtime(_lang);
+            let lang_map: HashMap<String, String> = self.load_language_map(_lang)?;
+            self.push_loaded(_lang, lang_map, modified);
             Ok(true)
+        } else if self.supported_languages.is_empty() {
+            self.log(LogLevel::Warn, "No supported languages found.");
+            Ok(false)
         } else {
-            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Language not supported"))
+            self.log(LogLevel::Error, "Not expected situation in langs_cache_manager.");
+            self.log(LogLevel::Error, "Clearing all caches.");
+            self.supported_languages_cache.clear();
+            self.lru_order.clear();
+            Ok(false)
         }
     }
 
-    pub fn reload_all(&mut self, config: &Config) -> Result<bool> {
+    // The cached map if there is one, otherwise a fresh load that leaves the cache untouched.
+    fn peek_language_map(&self, _lang: &str) -> Result<Cow<'_, HashMap<String, String>>> {
+        match self.supported_languages_cache.get(_lang) {
+            Some(lang_map) => Ok(Cow::Borrowed(lang_map)),
+            None => Ok(Cow::Owned(self.load_language_map(_lang)?)),
+        }
+    }
+
+    // Drops a language's cached map, e.g. because its file changed, and the handles built from it.
+    fn evict_language(&mut self, _lang: &str) {
+        self.supported_languages_cache.remove(_lang);
+        self.cached_mtimes.remove(_lang);
+        self.lru_order.retain(|l| l != _lang);
+        self.invalidate_handles(Some(_lang));
+    }
+
+    fn clear_cache(&mut self) {
         self.supported_languages_cache.clear();
+        self.cached_mtimes.clear();
         self.lru_order.clear();
-        self.rescan_languages(config)?;
-        Ok(true)
+        self.invalidate_handles(None);
     }
-}
 
-fn main() { 
-    match Localizer::new() {
-        Ok((mut _localizer, _config)) => {
-            println!("\nLocalizer initialized successfully.\n");
-            println!("Supported languages:");
-            for (lang_code, path) in &_localizer.supported_languages {
-                println!("Language Code: {}, File Path: {}", lang_code, path.display());
+    fn invalidate_handles(&mut self, _lang: Option<&str>) {
+        for table in &mut self.handle_tables {
+            if table.lang.is_some() && (_lang.is_none() || table.lang.as_deref() == _lang) {
+                table.lang = None;
+                table.keys.clear();
+                table.values.clear();
             }
+        }
+    }
 
-            println!("\nSupported languages map: {:?}\n", &_localizer.supported_languages);
-            println!("Current working directory: {}\n", _config.runtime_cwd.display());
+    // Inserts a freshly loaded map as most recently used and evicts down to the cache limit.
+    // Shared by the sync and async loaders; handles copied from an earlier read of _lang are
+    // dropped since the file may have changed in between. `modified` is the file's mtime from
+    // before the read (None for languages from a source).
+    fn push_loaded(&mut self, _lang: &str, lang_map: HashMap<String, String>, modified: Option<SystemTime>) {
+        self.invalidate_handles(Some(_lang));
+        self.lru_order.retain(|l| l != _lang);
+        self.lru_order.push(_lang.to_string());
+        self.supported_languages_cache.insert(_lang.to_string(), lang_map);
+        match modified {
+            Some(modified) => self.cached_mtimes.insert(_lang.to_string(), modified),
+            None => self.cached_mtimes.remove(_lang),
+        };
+        self.trim_cache_to(self.sup_lang_cache_limit);
+    }
 
+    fn file_mtime(&self, _lang: &str) -> Option<SystemTime> {
+        self.supported_languages.get(_lang).and_then(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+    }
 
-            println!("get_supported_languages() output:");
-            match _localizer.get_supported_languages() {
-                Ok(langs) => {
-                    for lang in langs {
-                        println!(" - {}", lang);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error retrieving supported languages: {}", e);
-                }
-            }
+    // File-backed languages take precedence over languages provided by the source.
+    fn has_language(&self, _lang: &str) -> bool {
+        self.supported_languages.contains_key(_lang) || self.source_languages.contains(_lang)
+    }
 
-            match _localizer.get_text_by_key("greeting", "en-lm", "en-GB") {
-                Ok(text) => {
-                    println!("\nText for key 'greeting' in 'en-GB': {}", text);
-                }
-                Err(e) => {
-                    eprintln!("Error retrieving text by key: {}", e);
-                }
-            }
+    fn load_language_map(&self, _lang: &str) -> Result<HashMap<String, String>> {
+        if !self.supported_languages.contains_key(_lang)
+            && let Some(source) = self.source.as_ref().filter(|_| self.source_languages.contains(_lang)) {
+            return source.load_language(_lang);
+        }
 
-            match _localizer.rescan_languages(&_config) {
-                Ok(_) => {
-                    println!("\nRescanned languages successfully.");
-                }
-                Err(e) => {
-                    eprintln!("Error rescanning languages: {}", e);
-                }
-            }
+        let path: &PathBuf = self.supported_languages.get(_lang)
+        .ok_or_else(|| LocalizerError::LanguageNotFound(_lang.to_string()))?;
+        let data: String = fs::read_to_string(path)?;
+        let root: serde_json::Value = parse_language_file(_lang, path, &data)?;
+        flatten_root(_lang, &root, &self.key_separator)
+    }
 
-            match _localizer.reload_language("en-GB") {
-                Ok(_) => {
-                    println!("\nReloaded language '{}' successfully.", "en-GB");
-                }
-                Err(e) => {
-                    eprintln!("Error reloading language '{}': {}", "en-GB", e);
-                }
+    fn scan_languages(config: &Config) -> Result<(HashMap<String, PathBuf>, ScanCollisions)> {
+        expect_directory(&config.language_file_dir_path)?;
+        let mut candidates: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for entry in fs::read_dir(&config.language_file_dir_path)? {
+            let Ok(entry) = entry else { continue };
+            let path = entry.path();
+            if path.is_file() && extension_rank(config, &path).is_some() {
+                let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+                candidates.entry(canonical_lang_code(stem)).or_default().push(path);
             }
+        }
 
-            match _localizer.reload_all(&_config) {
-                Ok(_) => {
-                    println!("\nReloaded all languages successfully.");
-                }
-                Err(e) => {
-                    eprintln!("Error reloading all languages: {}", e);
-                }
+        let mut lang_files: HashMap<String, PathBuf> = HashMap::new();
+        let mut collisions: ScanCollisions = Vec::new();
+        for (lang_code, mut paths) in candidates {
+            // Extension precedence first (JSON wins by default), then extensions spelled as
+            // configured (.json over .JSON), then files already named with the canonical code,
+            // then path order, so the result never depends on directory iteration order.
+            paths.sort_by_key(|path| {
+                let rank = extension_rank(config, path);
+                let exact_case = rank.and_then(|i| config.language_file_extensions.get(i))
+                    .is_some_and(|e| path.extension().and_then(|s| s.to_str()) == Some(e.trim_start_matches('.')));
+                (rank, !exact_case, path.file_stem().and_then(|s| s.to_str()) != Some(lang_code.as_str()), path.clone())
+            });
+            if paths.len() > 1 {
+                collisions.push((lang_code.clone(), paths.clone()));
             }
+            lang_files.insert(lang_code, paths.remove(0));
+        }
+        collisions.sort();
+        Ok((lang_files, collisions))
+    }
 
-            match _localizer.get_text_by_key("farewell", "en-UN", "en-GB") {
-                Ok(text) => 
-                    println!("\nText for key 'farewell' in 'en-UN' with fallback to 'en-GB': {}", text);
-                }
-                Err(e) => {
-                    eprintln!("Error retrieving text by key: {}", e);
-                }
-            }
+    fn log(&self, level: LogLevel, message: &str) {
+        (self.logger)(level, message);
+    }
+
+    // scan_languages has no Localizer to log through, so callers report its collisions.
+    fn log_collisions(&self) {
+        for (lang_code, paths) in &self.scan_collisions {
+            self.log(LogLevel::Warn, &format!("Warning: {} files map to language '{}', using {}", paths.len(), lang_code, paths[0].display()));
         }
-        Err(e) => {
+    }
+
+    fn rescan_if_stale(&mut self) -> Result<()> {
+        #[cfg(feature = "watch")]
+        self.apply_watch_events()?;
+        if let Some((ttl, config)) = &self.scan_ttl
+            && self.last_scan.elapsed() >= *ttl {
+            let config = config.clone();
+            self.rescan_languages(&config)?;
+        }
+        Ok(())
+    }
+
+    // Whether cached maps may answer without &mut self: false once a rescan is due or the
+    // watcher has changes to apply.
+    fn can_peek(&self) -> bool {
+        if self.scan_ttl.as_ref().is_some_and(|(ttl, _)| self.last_scan.elapsed() >= *ttl) {
+            return false;
+        }
+        #[cfg(feature = "watch")]
+        if self.watch_state.as_ref().is_some_and(|state| state.has_pending()) {
+            return false;
+        }
+        true
+    }
+
+    // Answers get_text_by_key from cached maps alone, or None if that needs a load, a rescan or
+    // would miss. A candidate that is not cached ends the walk, since it might have the key.
+    // The languages walked are appended to `used` for apply_read_hits.
+    fn peek_text(&self, _key: &str, _lang: &str, fallback: &str, used: &mut Vec<String>) -> Option<&String> {
+        if !self.can_peek() {
+            return None;
+        }
+        for lang in self.candidate_languages(_lang, fallback) {
+            let lang_map = self.supported_languages_cache.get(&lang)?;
+            used.push(lang);
+            if let Some(text) = lang_map.get(_key) {
+                return Some(text);
+            }
+        }
+        self.default_map.get(_key)
+    }
+
+    // get_texts from cached maps alone, under the same conditions as peek_text.
+    fn peek_texts(&self, keys: &[&str], _lang: &str, fallback: &str, used: &mut Vec<String>) -> Option<(HashMap<String, String>, Vec<String>)> {
+        if !self.can_peek() {
+            return None;
+        }
+        let mut texts: HashMap<String, String> = HashMap::new();
+        let mut pending: Vec<&str> = keys.to_vec();
+        for lang in self.candidate_languages(_lang, fallback) {
+            if pending.is_empty() {
+                break;
+            }
+            take_found(self.supported_languages_cache.get(&lang)?, &mut pending, &mut texts);
+            used.push(lang);
+        }
+        take_found(&self.default_map, &mut pending, &mut texts);
+        Some((texts, unique_missing(pending)))
+    }
+
+    // Counts hits SharedLocalizer answered under its read lock and moves those languages to the
+    // back of the LRU order, oldest first, as langs_cache_manager would have. Languages evicted
+    // in the meantime are skipped.
+    fn apply_read_hits(&mut self, hits: Vec<(String, u64)>) {
+        for (lang, count) in hits {
+            if self.supported_languages_cache.contains_key(&lang) {
+                self.stats.hits += count;
+                self.lru_order.retain(|l| *l != lang);
+                self.lru_order.push(lang);
+            }
+        }
+    }
+
+    fn lookup_text(&mut self, _key: &str, _lang: &str, fallback: &str) -> Result<String> {
+        self.rescan_if_stale()?;
+
+        let candidates: Vec<String> = self.candidate_languages(_lang, fallback);
+        self.find_in_languages(_key, &candidates)?
+        .ok_or_else(|| key_not_found(_key, _lang, &candidates))
+    }
+
+    // The supported language a requested tag refers to: the tag as given, then its canonical
+    // form, then the only supported language with the same primary subtag ("en" -> "en-GB"),
+    // preferring a bare "en" if there is one. Anything else comes back canonicalized.
+    fn resolve_lang(&self, _lang: &str) -> String {
+        if self.has_language(_lang) {
+            return _lang.to_string();
+        }
+        let canonical = canonical_lang_code(_lang);
+        if self.has_language(&canonical) {
+            return canonical;
+        }
+
+        let primary = canonical.split('-').next().unwrap_or("");
+        if self.has_language(primary) {
+            return primary.to_string();
+        }
+        let mut same_primary = self.supported_languages.keys().chain(self.source_languages.iter())
+            .filter(|lang| canonical_lang_code(lang).split('-').next() == Some(primary));
+        match (same_primary.next(), same_primary.next()) {
+            (Some(only), None) => only.clone(),
+            _ => canonical,
+        }
+    }
+
+    // A copy, so loading another language afterwards cannot evict what was read.
+    fn sorted_language_map(&mut self, _lang: &str) -> Result<BTreeMap<String, String>> {
+        let resolved = self.resolve_lang(_lang);
+        if !self.has_language(&resolved) {
+            return Err(LocalizerError::LanguageNotFound(_lang.to_string()));
+        }
+        self.langs_cache_manager("", &resolved)?;
+        let lang_map = self.supported_languages_cache.get(&resolved)
+        .ok_or_else(|| LocalizerError::LanguageNotFound(_lang.to_string()))?;
+        Ok(lang_map.iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+    }
+
+    // The requested language, then the fallback (also when the requested language exists but
+    // lacks the key), then the configured chain; unsupported ones are dropped. The default map
+    // comes after all of them, so it also covers a missing or empty languages directory.
+    // Each language is followed by its base languages, so "en-US" then "en" layer regional
+    // overrides over a shared file before the fallback ("zh-Hant-TW" -> "zh-Hant" -> "zh").
+    fn candidate_languages(&self, _lang: &str, fallback: &str) -> Vec<String> {
+        let effective_fallback = if fallback.is_empty() { "en-GB" } else { fallback };
+        let mut candidates: Vec<String> = Vec::new();
+        for lang in [_lang, effective_fallback].into_iter().chain(self.fallback_chain.iter().map(String::as_str)) {
+            let mut lang = self.resolve_lang(lang);
+            loop {
+                if !candidates.contains(&lang) {
+                    candidates.push(lang.clone());
+                }
+                match lang.rfind('-') {
+                    Some(end) => lang.truncate(end),
+                    None => break,
+                }
+            }
+        }
+        candidates.retain(|lang| self.has_language(lang));
+        candidates
+    }
+
+    // Walks `langs` in order until one has any of `keys` (tried in order within each language,
+    // e.g. "key@context" before "key"), then tries the default map the same way. Languages that
+    // exist but lack the keys are skipped like unsupported ones rather than ending the walk.
+    // Returns which of `langs` holds the text (None for the default map) and the key found.
+    fn locate_text<'k>(&mut self, keys: &[&'k str], langs: &[String]) -> Result<Option<TextLocation<'k>>> {
+        for (i, lang) in langs.iter().enumerate() {
+            if let Some(key) = self.key_in_language(keys, lang)? {
+                return Ok(Some((Some(i), key)));
+            }
+        }
+        Ok(keys.iter().find(|key| self.default_map.contains_key(**key)).map(|key| (None, *key)))
+    }
+
+    // The first of `keys` that `_lang` has, loading it if needed; None for unsupported languages.
+    fn key_in_language<'k>(&mut self, keys: &[&'k str], _lang: &str) -> Result<Option<&'k str>> {
+        if !self.has_language(_lang) {
+            return Ok(None);
+        }
+        if !self.langs_cache_manager(keys[0], _lang)? {
+            return Err(std::io::Error::other("Failed to manage language cache").into());
+        }
+        Ok(self.supported_languages_cache.get(_lang)
+            .and_then(|lang_map| keys.iter().find(|key| lang_map.contains_key(**key)).copied()))
+    }
+
+    // The value at a location returned by locate_text for the same `langs`.
+    fn located_text(&self, langs: &[String], (holder, _key): TextLocation<'_>) -> Option<&String> {
+        match holder {
+            Some(i) => self.supported_languages_cache.get(&langs[i])?.get(_key),
+            None => self.default_map.get(_key),
+        }
+    }
+
+    fn find_in_languages(&mut self, _key: &str, langs: &[String]) -> Result<Option<String>> {
+        let location = self.locate_text(&[_key], langs)?;
+        Ok(location.and_then(|location| self.located_text(langs, location)).cloned())
+    }
+
+    // Applies MissingKeyBehavior to the result of a non-interpolating lookup.
+    fn or_missing_key(&self, _key: &str, result: Result<String>) -> Result<String> {
+        match result {
+            Err(e) if e.is_not_found() && self.missing_key_behavior != MissingKeyBehavior::Error => {
+                Ok(_key.to_string())
+            }
+            result => result,
+        }
+    }
+
+    // The template interpolating lookups render, or None when a missing key should be
+    // returned verbatim (MissingKeyBehavior::ReturnKey).
+    fn resolve_template(&mut self, _key: &str, _lang: &str, fallback: &str) -> Result<Option<String>> {
+        let result = self.lookup_text(_key, _lang, fallback);
+        self.template_or_missing(_key, result)
+    }
+
+    fn template_or_missing(&self, _key: &str, result: Result<String>) -> Result<Option<String>> {
+        match result {
+            Ok(text) => Ok(Some(text)),
+            Err(e) if e.is_not_found() => match self.missing_key_behavior {
+                MissingKeyBehavior::Error => Err(e),
+                MissingKeyBehavior::ReturnKey => Ok(None),
+                MissingKeyBehavior::KeyAsMessage => Ok(Some(_key.to_string())),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn key_differences(&self, reference: &str) -> Result<KeyDifferences> {
+        let resolved = self.resolve_lang(reference);
+        if !self.has_language(&resolved) {
+            return Err(LocalizerError::LanguageNotFound(reference.to_string()));
+        }
+        let reference_keys = comparable_keys(&*self.peek_language_map(&resolved)?, &self.key_separator);
+
+        let mut differences: KeyDifferences = HashMap::new();
+        for lang in self.get_supported_languages()? {
+            if lang == resolved {
+                continue;
+            }
+            let lang_keys = comparable_keys(&*self.peek_language_map(&lang)?, &self.key_separator);
+            let mut missing: Vec<String> = reference_keys.difference(&lang_keys).cloned().collect();
+            let mut extra: Vec<String> = lang_keys.difference(&reference_keys).cloned().collect();
+            missing.sort();
+            extra.sort();
+            differences.insert(lang, (missing, extra));
+        }
+        Ok(differences)
+    }
+
+    // Public Functions
+    // Error policy: single-item methods are fail-fast and return the first error.
+    // Batch methods (reload_languages, preload) are collect-all: they run to completion and
+    // return every (language, error) pair instead of stopping at the first bad file.
+    pub fn new() -> Result<(Localizer, Config)> {
+        Localizer::builder().build()
+    }
+
+    /// Same as new(), but scanning `dir` for files with `extension` (e.g. "json" or ".json").
+    pub fn with_config(dir: impl Into<PathBuf>, extension: &str) -> Result<(Localizer, Config)> {
+        Localizer::builder().language_dir(dir).extension(extension).build()
+    }
+
+    /// Builds a Localizer from language code -> JSON text pairs, e.g. files embedded with
+    /// include_str!, without touching the filesystem. Keys are flattened with the default "."
+    /// separator, and reload_language reports an error for these languages.
+    pub fn from_embedded(files: &[(&str, &str)]) -> Result<Localizer> {
+        let mut localizer = Localizer::from_scan(HashMap::new(), Vec::new());
+        let source = EmbeddedSource::parse(files, &localizer.key_separator)?;
+        localizer.set_source(Box::new(source))?;
+        Ok(localizer)
+    }
+
+    pub fn builder() -> LocalizerBuilder {
+        LocalizerBuilder {
+            language_dir: PathBuf::from("./languages/"),
+            extensions: default_extensions(),
+            require_nonempty: false,
+            logger: None,
+        }
+    }
+
+    pub fn get_supported_languages(&self) -> Result<Vec<String>> {
+        let mut langs: Vec<String> = self.supported_languages.iter().filter_map(
+            |(lang_code, path)| {
+                if path.exists() {
+                    Some(lang_code.clone())
+                } else {
+                    None
+                }
+            }
+        ).collect();
+        langs.extend(self.source_languages.iter()
+            .filter(|lang| !self.supported_languages.contains_key(*lang))
+            .cloned());
+        Ok(langs)
+    }
+
+    /// On-disk file of a language, or None for languages served by a source (memory, SQLite).
+    pub fn language_path(&self, _lang: &str) -> Option<&Path> {
+        self.supported_languages.get(&self.resolve_lang(_lang)).map(PathBuf::as_path)
+    }
+
+    /// Loads `path` and registers it as `code` (canonicalized like add_language, "sv_se" ->
+    /// "sv-SE"), whatever the file is named. The registration lasts until the next rescan,
+    /// which only knows the files in the language directory.
+    pub fn load_language_as(&mut self, code: &str, path: impl Into<PathBuf>) -> Result<()> {
+        let path: PathBuf = path.into();
+        if !path.is_file() {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, format!("Language file not found: {}", path.display())).into());
+        }
+        let lang = canonical_lang_code(code);
+        let previous = self.supported_languages.insert(lang.clone(), path);
+        let modified = self.file_mtime(&lang);
+        match self.load_language_map(&lang) {
+            Ok(lang_map) => {
+                self.push_loaded(&lang, lang_map, modified);
+                Ok(())
+            }
+            Err(e) => {
+                match previous {
+                    Some(previous) => self.supported_languages.insert(lang, previous),
+                    None => self.supported_languages.remove(&lang),
+                };
+                Err(e)
+            }
+        }
+    }
+
+    /// Registers `path` as `_lang` without rescanning; it is loaded on first use. Replaces any
+    /// file registered for that language and drops its cached map, leaving others untouched.
+    /// Like load_language_as, the registration lasts until the next rescan (including one
+    /// triggered by the scan TTL), which only knows the files in the language directory.
+    pub fn add_language(&mut self, _lang: &str, path: PathBuf) -> Result<()> {
+        if !path.is_file() {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, format!("Language file not found: {}", path.display())).into());
+        }
+        let lang = canonical_lang_code(_lang);
+        self.supported_languages.insert(lang.clone(), path);
+        self.evict_language(&lang);
+        Ok(())
+    }
+
+    /// Stops serving `_lang` (file-backed or from the source) until the next rescan or
+    /// set_source, and drops its cached map. Returns whether it was supported.
+    pub fn remove_language(&mut self, _lang: &str) -> Result<bool> {
+        let lang = if self.has_language(_lang) { _lang.to_string() } else { canonical_lang_code(_lang) };
+        let from_file = self.supported_languages.remove(&lang).is_some();
+        self.scanned_stamps.remove(&lang);
+        let from_source = self.source_languages.remove(&lang);
+        self.scan_collisions.retain(|(l, _)| *l != lang);
+        self.evict_language(&lang);
+        Ok(from_file || from_source)
+    }
+
+    /// Attaches a source for languages that have no file in the language directory and
+    /// returns how many languages it provides. Replaces any previously attached source.
+    pub fn set_source(&mut self, source: Box<dyn LanguageSource>) -> Result<usize> {
+        let langs: Vec<String> = source.languages()?;
+        let previous: Vec<String> = self.source_languages.drain().collect();
+        for lang in previous {
+            if !self.supported_languages.contains_key(&lang) {
+                self.evict_language(&lang);
+            }
+        }
+        self.source_languages = langs.into_iter().collect();
+        self.source = Some(source);
+        Ok(self.source_languages.len())
+    }
+
+    /// Routes the library's diagnostics (scan warnings, lookup errors, ...) to `logger` instead
+    /// of stdout/stderr. Pass `Box::new(|_, _| {})` to silence them.
+    pub fn set_logger(&mut self, logger: Logger) {
+        self.logger = logger;
+    }
+
+    pub fn set_missing_key_behavior(&mut self, behavior: MissingKeyBehavior) {
+        self.missing_key_behavior = behavior;
+    }
+
+    /// Languages tried in order when the resolved language lacks a key, before the default map.
+    /// Fails without changing the current chain if any entry is not a supported language.
+    pub fn set_fallback_chain(&mut self, chain: &[&str]) -> Result<bool> {
+        let resolved: Vec<String> = chain.iter().map(|lang| self.resolve_lang(lang)).collect();
+        let unknown: Vec<&str> = chain.iter().zip(&resolved)
+            .filter(|(_, lang)| !self.has_language(lang))
+            .map(|(requested, _)| *requested)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(LocalizerError::InvalidInput(format!("Unknown languages in fallback chain: {}", unknown.join(", "))));
+        }
+        self.fallback_chain = resolved;
+        Ok(true)
+    }
+
+    pub fn get_text_by_key(&mut self, _key: &str, _lang: &str, fallback: &str) -> Result<String> {
+        let result = self.lookup_text(_key, _lang, fallback);
+        self.or_missing_key(_key, result)
+    }
+
+    /// Resolves like get_text_by_key and passes the cached value to `f` in place instead of
+    /// copying it into a String, for hot rendering paths. With MissingKeyBehavior other than
+    /// Error, `f` receives the key when nothing has it.
+    pub fn with_text<R>(&mut self, _key: &str, _lang: &str, fallback: &str, f: impl FnOnce(&str) -> R) -> Result<R> {
+        self.rescan_if_stale()?;
+        let candidates: Vec<String> = self.candidate_languages(_lang, fallback);
+        let location = self.locate_text(&[_key], &candidates)?;
+        match location.and_then(|location| self.located_text(&candidates, location)) {
+            Some(text) => Ok(f(text)),
+            None if self.missing_key_behavior != MissingKeyBehavior::Error => Ok(f(_key)),
+            None => Err(key_not_found(_key, _lang, &candidates)),
+        }
+    }
+
+    /// Looks up `_key@context` (e.g. "post@button") and falls back to the bare key, per
+    /// language: a bare key in the requested language beats a contextual one in the fallback.
+    /// An empty context behaves like get_text_by_key.
+    pub fn get_text_with_context(&mut self, _key: &str, context: &str, _lang: &str, fallback: &str) -> Result<String> {
+        if context.is_empty() {
+            return self.get_text_by_key(_key, _lang, fallback);
+        }
+        self.rescan_if_stale()?;
+        let contextual = format!("{}@{}", _key, context);
+        let candidates: Vec<String> = self.candidate_languages(_lang, fallback);
+        let location = self.locate_text(&[&contextual, _key], &candidates)?;
+        let result = location.and_then(|location| self.located_text(&candidates, location)).cloned()
+        .ok_or_else(|| key_not_found(_key, _lang, &candidates));
+        self.or_missing_key(_key, result)
+    }
+
+    /// Like get_text_by_key, but never fails: a missing key comes back as the key itself,
+    /// whatever the MissingKeyBehavior, so untranslated strings show up on screen. IO and
+    /// parse errors go to the logger (see set_logger) and also yield the key.
+    pub fn get_text_or_key(&mut self, _key: &str, _lang: &str, fallback: &str) -> String {
+        match self.lookup_text(_key, _lang, fallback) {
+            Ok(text) => text,
+            Err(e) if e.is_not_found() => _key.to_string(),
+            Err(e) => {
+                self.log(LogLevel::Error, &format!("Error looking up '{}' in '{}': {}", _key, _lang, e));
+                _key.to_string()
+            }
+        }
+    }
+
+    /// Resolves many keys with the same fallback rules as get_text_by_key, touching each
+    /// language's cache entry once instead of once per key. Returns the texts found and the
+    /// keys nothing had; misses never abort the batch and MissingKeyBehavior is not applied.
+    pub fn get_texts(&mut self, keys: &[&str], _lang: &str, fallback: &str) -> Result<(HashMap<String, String>, Vec<String>)> {
+        self.rescan_if_stale()?;
+        let mut texts: HashMap<String, String> = HashMap::new();
+        let mut pending: Vec<&str> = keys.to_vec();
+
+        // Each map is drained right after loading, so a later load evicting it does no harm.
+        for lang in self.candidate_languages(_lang, fallback) {
+            if pending.is_empty() {
+                break;
+            }
+            if !self.langs_cache_manager("", &lang)? {
+                return Err(std::io::Error::other("Failed to manage language cache").into());
+            }
+            if let Some(lang_map) = self.supported_languages_cache.get(&lang) {
+                take_found(lang_map, &mut pending, &mut texts);
+            }
+        }
+        take_found(&self.default_map, &mut pending, &mut texts);
+        Ok((texts, unique_missing(pending)))
+    }
+
+    /// Tries each language of `chain` in order until one has the key, then the default map.
+    /// The chain replaces both the single fallback and the configured fallback chain. If
+    /// nothing has the key the error lists the chain that was tried.
+    pub fn get_text_by_key_chain(&mut self, _key: &str, chain: &[&str]) -> Result<String> {
+        self.rescan_if_stale()?;
+        let resolved: Vec<String> = chain.iter().map(|lang| self.resolve_lang(lang)).collect();
+        let chain: Vec<String> = chain.iter().map(|lang| lang.to_string()).collect();
+        let result = match self.find_in_languages(_key, &resolved)? {
+            Some(text) => Ok(text),
+            None => Err(LocalizerError::KeyNotFoundInChain { key: _key.to_string(), chain }),
+        };
+        self.or_missing_key(_key, result)
+    }
+
+    /// Resolves like get_text_by_key and fills each {name} placeholder from `args`.
+    /// Placeholders without an argument are left untouched, and "{{" / "}}" render as braces.
+    pub fn get_text_with_args(&mut self, _key: &str, _lang: &str, fallback: &str, args: &HashMap<String, String>) -> Result<String> {
+        match self.resolve_template(_key, _lang, fallback)? {
+            Some(template) => Ok(interpolate(&template, args)),
+            None => Ok(_key.to_string()),
+        }
+    }
+
+    /// Like get_text_with_args, and also returns the placeholders that had no argument (left as
+    /// "{name}" in the text), so typos in argument names can be caught during testing.
+    pub fn get_text_with_args_checked(&mut self, _key: &str, _lang: &str, fallback: &str, args: &HashMap<String, String>) -> Result<(String, Vec<String>)> {
+        match self.resolve_template(_key, _lang, fallback)? {
+            Some(template) => Ok((interpolate(&template, args), unfilled_placeholders(&template, args))),
+            None => Ok((_key.to_string(), Vec::new())),
+        }
+    }
+
+    /// Like get_text_with_args, with every argument escaped for the output context first.
+    pub fn get_text_with_args_escaped(&mut self, _key: &str, _lang: &str, fallback: &str, args: &HashMap<String, String>, mode: EscapeMode) -> Result<String> {
+        let escaped: HashMap<String, String> = args.iter()
+            .map(|(name, value)| (name.clone(), escape_value(value, mode)))
+            .collect();
+        self.get_text_with_args(_key, _lang, fallback, &escaped)
+    }
+
+    /// Like get_text_with_args, but errors with the names of every placeholder that has no
+    /// argument instead of rendering it as "{name}".
+    pub fn get_text_strict_fmt(&mut self, _key: &str, _lang: &str, fallback: &str, args: &HashMap<String, String>) -> Result<String> {
+        let Some(template) = self.resolve_template(_key, _lang, fallback)? else {
+            return Ok(_key.to_string());
+        };
+
+        let unfilled = unfilled_placeholders(&template, args);
+        if !unfilled.is_empty() {
+            return Err(LocalizerError::UnfilledPlaceholders { key: _key.to_string(), names: unfilled });
+        }
+        Ok(interpolate(&template, args))
+    }
+
+    /// Picks one of the variants stored under "key.0", "key.1", ... (joined with the key separator).
+    pub fn get_variant(&mut self, _key: &str, _lang: &str, fallback: &str, strategy: VariantStrategy) -> Result<String> {
+        self.rescan_if_stale()?;
+        // Every variant and weight is read from the first language (or the default map) that has
+        // "key.0", so a partly translated set is never mixed with another language's variants.
+        let first_key = format!("{}{}0", _key, self.key_separator);
+        let candidates: Vec<String> = self.candidate_languages(_lang, fallback);
+        let holder: Option<usize> = self.locate_text(&[&first_key], &candidates)?.and_then(|(holder, _)| holder);
+        let variant_map: &HashMap<String, String> = match holder {
+            Some(i) => self.supported_languages_cache.get(&candidates[i])
+                .ok_or_else(|| LocalizerError::LanguageNotFound(candidates[i].clone()))?,
+            None => &self.default_map,
+        };
+        let variant_lang: &str = holder.map(|i| candidates[i].as_str()).unwrap_or(_lang);
+
+        let mut variants: Vec<String> = Vec::new();
+        while let Some(text) = variant_map.get(&format!("{}{}{}", _key, self.key_separator, variants.len())) {
+            variants.push(text.clone());
+        }
+        if variants.is_empty() {
+            return Err(LocalizerError::KeyNotFound { key: _key.to_string(), lang: _lang.to_string() });
+        }
+
+        let index = match strategy {
+            VariantStrategy::Index(n) => n % variants.len(),
+            VariantStrategy::Random => (random_u64() % variants.len() as u64) as usize,
+            VariantStrategy::WeightedRandom => {
+                let mut weights: Vec<f64> = Vec::with_capacity(variants.len());
+                for i in 0..variants.len() {
+                    let weight_key = format!("{}{}{}{}weight", _key, self.key_separator, i, self.key_separator);
+                    let weight = match variant_map.get(&weight_key) {
+                        Some(text) => text.trim().parse::<f64>().ok()
+                            .filter(|w| w.is_finite() && *w >= 0.0)
+                            .ok_or_else(|| LocalizerError::ParseError { lang: variant_lang.to_string(), source: format!("Invalid variant weight at '{}'", weight_key).into() })?,
+                        None => 1.0,
+                    };
+                    weights.push(weight);
+                }
+
+                let total: f64 = weights.iter().sum();
+                if total == 0.0 {
+                    (random_u64() % variants.len() as u64) as usize
+                } else {
+                    let mut roll = (random_u64() as f64 / u64::MAX as f64) * total;
+                    weights.iter().position(|w| {
+                        roll -= w;
+                        *w > 0.0 && roll < 0.0
+                    }).unwrap_or_else(|| weights.iter().rposition(|w| *w > 0.0).unwrap_or(0))
+                }
+            }
+        };
+        Ok(variants.swap_remove(index))
+    }
+
+    /// Looks up the plural form of `_key` for `count`, e.g. "items.one" or "items.few", falling
+    /// back to "items.other" when that form is absent. The CLDR category is picked separately for
+    /// each language tried, so a fallback language's text uses its own rules; the default map
+    /// uses `_lang`'s. "{count}" in the text is replaced with `count`.
+    pub fn get_plural(&mut self, _key: &str, count: i64, _lang: &str, fallback: &str) -> Result<String> {
+        self.rescan_if_stale()?;
+        let plural_keys = |lang: &str, separator: &str| [
+            format!("{}{}{}", _key, separator, plural_category(lang, count)),
+            format!("{}{}other", _key, separator),
+        ];
+        let candidates: Vec<String> = self.candidate_languages(_lang, fallback);
+        let mut found: Option<String> = None;
+        for lang in &candidates {
+            let keys = plural_keys(lang, &self.key_separator);
+            if let Some(key) = self.key_in_language(&[&keys[0], &keys[1]], lang)? {
+                found = self.supported_languages_cache.get(lang).and_then(|lang_map| lang_map.get(key)).cloned();
+                break;
+            }
+        }
+        let keys = plural_keys(_lang, &self.key_separator);
+        let result = found.or_else(|| keys.iter().find_map(|key| self.default_map.get(key)).cloned())
+            .ok_or_else(|| key_not_found(_key, _lang, &candidates));
+        match self.template_or_missing(_key, result)? {
+            Some(template) => Ok(interpolate(&template, &HashMap::from([(String::from("count"), count.to_string())]))),
+            None => Ok(_key.to_string()),
+        }
+    }
+
+    /// Resolves `keys` in `_lang` once so a hot loop can read them with get_by_handle without
+    /// hashing. Values are copied from the requested language only (tags like "en_gb" resolve as in
+    /// get_text_by_key, but there is no fallback); a key it lacks gets a
+    /// handle that resolves to None. Handles stop resolving when the language is reloaded.
+    /// Preparing the same keys for the same language again returns the existing handles.
+    pub fn prepare_keys(&mut self, _lang: &str, keys: &[&str]) -> Result<Vec<KeyHandle>> {
+        let resolved = self.resolve_lang(_lang);
+        if !self.has_language(&resolved) {
+            return Err(LocalizerError::LanguageNotFound(_lang.to_string()));
+        }
+        self.langs_cache_manager("", &resolved)?;
+        // Checked after the cache walk, which may have reloaded the language and invalidated the table.
+        if let Some((slot, table)) = self.handle_tables.iter().enumerate()
+            .find(|(_, table)| table.lang.as_deref() == Some(resolved.as_str()) && table.keys.iter().map(String::as_str).eq(keys.iter().copied())) {
+            return Ok((0..keys.len()).map(|index| KeyHandle { slot, index, generation: table.generation }).collect());
+        }
+        let lang_map = self.supported_languages_cache.get(&resolved)
+        .ok_or_else(|| LocalizerError::LanguageNotFound(_lang.to_string()))?;
+        let values: Vec<Option<String>> = keys.iter().map(|key| lang_map.get(*key).cloned()).collect();
+
+        self.handle_generation += 1;
+        let table = HandleTable {
+            lang: Some(resolved),
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+            generation: self.handle_generation,
+            values,
+        };
+        // Reuse a slot freed by invalidation so repeated reloads don't grow the table list.
+        let slot = match self.handle_tables.iter().position(|table| table.lang.is_none()) {
+            Some(slot) => {
+                self.handle_tables[slot] = table;
+                slot
+            }
+            None => {
+                self.handle_tables.push(table);
+                self.handle_tables.len() - 1
+            }
+        };
+        Ok((0..keys.len()).map(|index| KeyHandle { slot, index, generation: self.handle_generation }).collect())
+    }
+
+    pub fn get_by_handle(&self, handle: KeyHandle) -> Option<&str> {
+        let table = self.handle_tables.get(handle.slot)?;
+        if table.generation != handle.generation || table.lang.is_none() {
+            return None;
+        }
+        table.values.get(handle.index)?.as_deref()
+    }
+
+    /// Like get_text_by_key, but errors unless the requested language (after tag resolution,
+    /// e.g. "en_gb" -> "en-GB") has the key. The fallback language, base languages and the
+    /// default map are never consulted; a language served by
+    /// an attached source counts as the exact language.
+    pub fn get_text_strict(&mut self, _key: &str, _lang: &str) -> Result<String> {
+        let resolved = self.resolve_lang(_lang);
+        if !self.has_language(&resolved) {
+            return Err(LocalizerError::LanguageNotFound(_lang.to_string()));
+        }
+        self.langs_cache_manager(_key, &resolved)?;
+        self.supported_languages_cache.get(&resolved)
+        .and_then(|lang_map| lang_map.get(_key))
+        .cloned()
+        .ok_or_else(|| LocalizerError::KeyNotFound { key: _key.to_string(), lang: _lang.to_string() })
+    }
+
+    /// Makes lookups rescan `config`'s directory when the last scan is older than `ttl`,
+    /// giving approximate hot-reload without a watcher. `None` disables it (the default).
+    pub fn set_scan_ttl(&mut self, config: &Config, ttl: Option<Duration>) {
+        self.scan_ttl = ttl.map(|ttl| (ttl, config.clone()));
+    }
+
+    /// Languages that more than one file normalized to during the last scan, e.g. both
+    /// en_GB.json and en-GB.json. The first path of each entry is the file in use.
+    pub fn scan_collisions(&self) -> &[(String, Vec<PathBuf>)] {
+        &self.scan_collisions
+    }
+
+    /// Scans like the constructors do, but fails with DuplicateLanguages listing every
+    /// language several files map to instead of warning and picking one.
+    pub fn scan_languages_strict(config: &Config) -> Result<HashMap<String, PathBuf>> {
+        let (_lang_files, _collisions) = Localizer::scan_languages(config)?;
+        if !_collisions.is_empty() {
+            return Err(LocalizerError::DuplicateLanguages(_collisions));
+        }
+        Ok(_lang_files)
+    }
+
+    /// Rescans the language directory, picking up new files and dropping deleted ones. Only
+    /// cached languages whose file was removed, replaced or modified (a different mtime or
+    /// length) since the last scan are evicted; the rest keep their cached maps.
+    pub fn rescan_languages(&mut self, config: &Config) -> Result<bool> {
+        let (_lang_files, _collisions) = Localizer::scan_languages(config)?;
+        let _stamps = file_stamps(&_lang_files);
+
+        // Languages that are file-backed neither before nor after come from the source.
+        let changed: Vec<String> = self.supported_languages_cache.keys().filter(|lang| {
+            let (old, new) = (self.supported_languages.get(*lang), _lang_files.get(*lang));
+            (old.is_some() || new.is_some())
+                && (old != new || !_stamps.contains_key(*lang) || _stamps.get(*lang) != self.scanned_stamps.get(*lang))
+        }).cloned().collect();
+
+        self.supported_languages = _lang_files;
+        self.scanned_stamps = _stamps;
+        self.scan_collisions = _collisions;
+        self.last_scan = Instant::now();
+        self.log_collisions();
+
+        for lang in changed {
+            self.evict_language(&lang);
+        }
+        Ok(true)
+    }
+
+    pub fn reload_language(&mut self, _lang: &str) -> Result<bool> {
+        let resolved = self.resolve_lang(_lang);
+        if !self.supported_languages.contains_key(&resolved)
+            && self.source.as_ref().is_some_and(|source| !source.reloadable() && self.source_languages.contains(&resolved)) {
+            return Err(LocalizerError::InvalidInput(format!("Language '{}' is embedded and cannot be reloaded", _lang)));
+        }
+        if self.has_language(&resolved) {
+            self.evict_language(&resolved);
+            
+            self.langs_cache_manager("", &resolved)?;
+            Ok(true)
+        } else {
+            Err(LocalizerError::LanguageNotFound(_lang.to_string()))
+        }
+    }
+
+    pub fn reload_languages(&mut self, langs: &[&str]) -> Vec<(String, LocalizerError)> {
+        let mut errors: Vec<(String, LocalizerError)> = Vec::new();
+        for lang in langs {
+            if let Err(e) = self.reload_language(lang) {
+                errors.push((lang.to_string(), e));
+            }
+        }
+        errors
+    }
+
+    /// Loads `langs` into the cache ahead of the first lookup, so the read and parse cost is paid
+    /// up front. The cache limit is respected: preloading more languages than the limit keeps
+    /// only the last ones. Returns how many were loaded plus the errors of those that failed.
+    pub fn preload(&mut self, langs: &[&str]) -> (usize, Vec<(String, LocalizerError)>) {
+        let mut loaded = 0;
+        let mut errors: Vec<(String, LocalizerError)> = Vec::new();
+        for lang in langs {
+            let resolved = self.resolve_lang(lang);
+            let result = if self.has_language(&resolved) {
+                self.langs_cache_manager("", &resolved)
+            } else {
+                Err(LocalizerError::LanguageNotFound(lang.to_string()))
+            };
+            match result {
+                Ok(_) => loaded += 1,
+                Err(e) => errors.push((lang.to_string(), e)),
+            }
+        }
+        (loaded, errors)
+    }
+
+    /// Preloads every supported language. The cache limit is lifted only while preloading, so
+    /// every language ends up cached; the limit itself is unchanged, and the cache is trimmed
+    /// back down to it the next time a language has to be loaded.
+    pub fn preload_all(&mut self) -> Result<(usize, Vec<(String, LocalizerError)>)> {
+        let langs: Vec<String> = self.get_supported_languages()?;
+        let limit = self.sup_lang_cache_limit;
+        self.sup_lang_cache_limit = limit.max(langs.len());
+        let langs: Vec<&str> = langs.iter().map(String::as_str).collect();
+        let result = self.preload(&langs);
+        self.sup_lang_cache_limit = limit;
+        Ok(result)
+    }
+
+    pub fn reload_all(&mut self, config: &Config) -> Result<bool> {
+        self.clear_cache();
+        self.rescan_languages(config)?;
+        Ok(true)
+    }
+
+    /// Sets built-in strings (usually a `const` slice compiled into the binary) that are
+    /// consulted after every file-based language, so critical text is available even
+    /// with no translation files present.
+    pub fn set_default_map(&mut self, entries: &[(&str, &str)]) {
+        self.default_map = entries.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+    }
+
+    /// Changes how many languages stay cached and evicts least recently used ones down to it
+    /// right away. A limit of 0 is rejected, since the language being looked up must fit.
+    pub fn set_cache_limit(&mut self, limit: usize) -> Result<usize> {
+        if limit == 0 {
+            return Err(LocalizerError::InvalidInput(String::from("Cache limit must be at least 1")));
+        }
+        self.sup_lang_cache_limit = limit;
+        Ok(self.trim_cache_to(limit))
+    }
+
+    /// Evicts least recently used languages until at most `target` remain cached and returns
+    /// how many were evicted. Unlike the cache limit, this is a one-off and changes no policy.
+    pub fn trim_cache_to(&mut self, target: usize) -> usize {
+        let mut evicted = 0;
+        while self.supported_languages_cache.len() > target && !self.lru_order.is_empty() {
+            let oldest_lang = self.lru_order.remove(0);
+            if self.supported_languages_cache.remove(&oldest_lang).is_some() {
+                evicted += 1;
+            }
+            self.cached_mtimes.remove(&oldest_lang);
+        }
+        self.stats.evictions += evicted as u64;
+        evicted
+    }
+
+    /// Hit, miss and eviction counts of the language cache, for tuning the cache limit.
+    /// Lookups answered under SharedLocalizer's read lock are counted once it next takes the write lock.
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            cached_languages: self.supported_languages_cache.len(),
+            limit: self.sup_lang_cache_limit,
+            ..self.stats
+        }
+    }
+
+    /// Zeroes the hit, miss and eviction counters, e.g. at the start of a metrics interval.
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Changes the separator used to flatten nested objects (default ".").
+    /// Cached maps are dropped, and keys must be requested with the new separator afterwards.
+    pub fn set_key_separator(&mut self, separator: &str) -> Result<bool> {
+        if separator.is_empty() {
+            return Err(LocalizerError::InvalidInput(String::from("Key separator cannot be empty")));
+        }
+        self.key_separator = separator.to_string();
+        self.clear_cache();
+        Ok(true)
+    }
+
+    pub fn key_separator(&self) -> &str {
+        &self.key_separator
+    }
+
+    /// Orders supported languages by their "_meta" field `key` (e.g. "name" reads "_meta.name"),
+    /// ties broken by language code. Languages without the field come last, ordered by code.
+    /// Uncached languages are read without being added to the cache.
+    pub fn supported_languages_sorted_by(&mut self, key: &str) -> Result<Vec<String>> {
+        let meta_key = format!("_meta{}{}", self.key_separator, key);
+        let mut entries: Vec<(Option<String>, String)> = Vec::new();
+        for lang in self.get_supported_languages()? {
+            let value = self.peek_language_map(&lang)?.get(&meta_key).cloned();
+            entries.push((value, lang));
+        }
+        entries.sort_by(|(a_value, a_lang), (b_value, b_lang)| match (a_value, b_value) {
+            (Some(a), Some(b)) => a.cmp(b).then_with(|| a_lang.cmp(b_lang)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a_lang.cmp(b_lang),
+        });
+        Ok(entries.into_iter().map(|(_, lang)| lang).collect())
+    }
+
+    /// Percentage (0-100) of the reference language's keys present in the other languages.
+    /// Every non-reference language weighs the same regardless of its size, so one nearly empty
+    /// language pulls the number down as much as one nearly complete language pushes it up.
+    /// Returns 100 when there are no other languages. Uncached languages are not added to the cache.
+    pub fn overall_coverage(&mut self, reference: &str) -> Result<f32> {
+        let resolved = self.resolve_lang(reference);
+        if !self.has_language(&resolved) {
+            return Err(LocalizerError::LanguageNotFound(reference.to_string()));
+        }
+        let reference_keys = comparable_keys(&*self.peek_language_map(&resolved)?, &self.key_separator);
+        if reference_keys.is_empty() {
+            return Err(LocalizerError::InvalidInput(format!("Reference language '{}' has no keys", reference)));
+        }
+
+        let mut coverages: Vec<f32> = Vec::new();
+        for lang in self.get_supported_languages()? {
+            if lang == resolved {
+                continue;
+            }
+            let lang_keys = comparable_keys(&*self.peek_language_map(&lang)?, &self.key_separator);
+            let present = reference_keys.intersection(&lang_keys).count();
+            coverages.push(present as f32 / reference_keys.len() as f32);
+        }
+        if coverages.is_empty() {
+            return Ok(100.0);
+        }
+        Ok(coverages.iter().sum::<f32>() / coverages.len() as f32 * 100.0)
+    }
+
+    /// For every other supported language that lacks keys the reference has, the missing keys
+    /// (sorted). An empty map means every language is complete. Languages that are not cached
+    /// are loaded just for the comparison and the cache is left as it was. Plural forms count
+    /// as one key per base ("new_messages"), whichever categories a language uses.
+    pub fn validate_against(&mut self, reference_lang: &str) -> Result<HashMap<String, Vec<String>>> {
+        Ok(self.key_differences(reference_lang)?.into_iter()
+            .filter_map(|(lang, (missing, _))| (!missing.is_empty()).then_some((lang, missing)))
+            .collect())
+    }
+
+    /// Like validate_against, but reports the keys each language has that the reference lacks.
+    pub fn extra_keys_against(&mut self, reference_lang: &str) -> Result<HashMap<String, Vec<String>>> {
+        Ok(self.key_differences(reference_lang)?.into_iter()
+            .filter_map(|(lang, (_, extra))| (!extra.is_empty()).then_some((lang, extra)))
+            .collect())
+    }
+
+    /// Collects the distinct placeholder names used across every value of a language.
+    pub fn placeholders_in_language(&mut self, _lang: &str) -> Result<HashSet<String>> {
+        let resolved = self.resolve_lang(_lang);
+        if !self.has_language(&resolved) {
+            return Err(LocalizerError::LanguageNotFound(_lang.to_string()));
+        }
+        self.langs_cache_manager("", &resolved)?;
+        let lang_map = self.supported_languages_cache.get(&resolved)
+        .ok_or_else(|| LocalizerError::LanguageNotFound(_lang.to_string()))?;
+
+        Ok(lang_map.values().flat_map(|value| parse_placeholders(value)).collect())
+    }
+
+    /// Returns every entry under `prefix`, e.g. "menu" matches "menu.open" but not "menubar".
+    pub fn get_by_prefix(&mut self, prefix: &str, _lang: &str) -> Result<HashMap<String, String>> {
+        let resolved = self.resolve_lang(_lang);
+        if !self.has_language(&resolved) {
+            return Err(LocalizerError::LanguageNotFound(_lang.to_string()));
+        }
+        self.langs_cache_manager(prefix, &resolved)?;
+        let lang_map = self.supported_languages_cache.get(&resolved)
+        .ok_or_else(|| LocalizerError::LanguageNotFound(_lang.to_string()))?;
+
+        let nested_prefix = format!("{}{}", prefix, self.key_separator);
+        Ok(lang_map.iter()
+            .filter(|(key, _)| key.as_str() == prefix || key.starts_with(&nested_prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect())
+    }
+
+    /// Serializes a language's flattened map as pretty JSON with sorted keys, so successive
+    /// exports diff cleanly. The language is loaded into the cache if it is not already.
+    pub fn export_language(&mut self, _lang: &str) -> Result<String> {
+        let catalog = self.sorted_language_map(_lang)?;
+        serialize_catalog(&catalog)
+    }
+
+    /// Like export_language, with `fallback`'s entries filling keys `_lang` lacks; entries of
+    /// `_lang` always win. Diffing this against export_language shows what is untranslated.
+    pub fn export_merged(&mut self, _lang: &str, fallback: &str) -> Result<String> {
+        let mut catalog = self.sorted_language_map(fallback)?;
+        catalog.extend(self.sorted_language_map(_lang)?);
+        serialize_catalog(&catalog)
+    }
+}
+
+// Construction options; Localizer::new() builds with the defaults.
+struct LocalizerBuilder {
+    language_dir: PathBuf,
+    extensions: Vec<String>,
+    require_nonempty: bool,
+    logger: Option<Logger>,
+}
+
+impl LocalizerBuilder {
+    /// Directory scanned for language files (default "./languages/"); created if missing.
+    pub fn language_dir(mut self, dir: impl Into<PathBuf>) -> LocalizerBuilder {
+        self.language_dir = dir.into();
+        self
+    }
+
+    /// Scan only files with `extension`, with or without the leading dot. The default is
+    /// ".json" plus ".yaml"/".yml" and ".toml" when those features are enabled.
+    pub fn extension(mut self, extension: &str) -> LocalizerBuilder {
+        self.extensions = vec![extension.to_string()];
+        self
+    }
+
+    /// Scan files with any of `extensions`. When several files map to one language, the one
+    /// whose extension comes first in `extensions` is used.
+    pub fn extensions(mut self, extensions: &[&str]) -> LocalizerBuilder {
+        self.extensions = extensions.iter().map(|e| e.to_string()).collect();
+        self
+    }
+
+    /// Fail construction with an error naming the scanned directory when it has no language files.
+    pub fn require_nonempty(mut self, require: bool) -> LocalizerBuilder {
+        self.require_nonempty = require;
+        self
+    }
+
+    /// Logger used from construction on, so the scan's own messages go through it too.
+    pub fn logger(mut self, logger: Logger) -> LocalizerBuilder {
+        self.logger = Some(logger);
+        self
+    }
+
+    pub fn build(self) -> Result<(Localizer, Config)> {
+        let extensions: Vec<String> = self.extensions.iter().map(|e| format!(".{}", e.trim_start_matches('.'))).collect();
+        if extensions.is_empty() || extensions.iter().any(|e| e == ".") {
+            return Err(LocalizerError::InvalidInput(String::from("Language file extension cannot be empty")));
+        }
+
+        let ldsp = self.language_dir;
+        expect_directory(&ldsp)?;
+        match fs::create_dir_all(&ldsp) {
+            Ok(_) => {},
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {},
+            Err(e) => return Err(e.into()),
+        }
+        let ldsp = fs::canonicalize(&ldsp)?;
+
+        let config = Config {
+            runtime_cwd: env::current_dir()?,
+            language_file_dir_path: ldsp.clone(),
+            language_file_extensions: extensions,
+        };
+
+        if !config.language_file_dir_path.exists() {
+            fs::create_dir_all(&config.language_file_dir_path)?;
+        }
+        let (_lang_files, _collisions) = Localizer::scan_languages(&config)?;
+        if self.require_nonempty && _lang_files.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, format!("No language files found in {}", config.language_file_dir_path.display())).into());
+        }
+
+        let mut localizer = Localizer::from_scan(_lang_files, _collisions);
+        if let Some(logger) = self.logger {
+            localizer.logger = logger;
+        }
+        localizer.log_collisions();
+        localizer.log(LogLevel::Info, &format!("Language files found: {}", localizer.supported_languages.len()));
+        Ok((localizer, config))
+    }
+}
+
+const BUILTIN_STRINGS: &[(&str, &str)] = &[
+    ("loading", "Loading"),
+    ("error_generic", "Something went wrong"),
+    ("tip.0", "Press F1 for help"),
+    ("tip.1", "Settings live under the menu"),
+    ("tip.2", "You found the rare tip!"),
+    ("tip.2.weight", "0.1"),
+];
+
+fn main() { 
+    if let Err(e) = Localizer::builder().require_nonempty(true).extensions(&["json", "yaml", "yml", "toml"]).logger(Box::new(|_, _| {})).build() {
+        eprintln!("Localizer with require_nonempty could not be built: {}", e);
+    }
+    match Localizer::with_config("./languages", "json") {
+        Ok((_, config)) => {
+            println!("Localizer configured for {} with extensions {:?}", config.language_file_dir_path.display(), config.language_file_extensions);
+        }
+        Err(e) => {
+            eprintln!("Localizer with a custom config could not be built: {}", e);
+        }
+    }
+
+    match Localizer::from_embedded(&[("en-GB", include_str!("../languages/en-GB.json")), ("fr_fr", r#"{"menu": {"file": {"open": "Ouvrir"}}}"#)]) {
+        Ok(mut embedded) => {
+            for lang in ["en-GB", "fr-FR"] {
+                match embedded.get_text_by_key("menu.file.open", lang, lang) {
+                    Ok(text) => {
+                        println!("Embedded text for key 'menu.file.open' in '{}': {}", lang, text);
+                    }
+                    Err(e) => {
+                        eprintln!("Error reading embedded '{}': {}", lang, e);
+                    }
+                }
+            }
+            if let Err(e) = embedded.reload_language("fr-FR") {
+                eprintln!("Reloading an embedded language failed as expected: {}", e);
+            }
+        }
+        Err(e) => {
+            eprintln!("Localizer from embedded files could not be built: {}", e);
+        }
+    }
+
+    match Localizer::new() {
+        Ok((mut _localizer, _config)) => {
+            println!("\nLocalizer initialized successfully.\n");
+            _localizer.set_logger(Box::new(|level, message| eprintln!("[localizer {:?}] {}", level, message)));
+            _localizer.set_default_map(BUILTIN_STRINGS);
+            println!("Supported languages:");
+            for (lang_code, path) in &_localizer.supported_languages {
+                println!("Language Code: {}, File Path: {}", lang_code, path.display());
+            }
+
+            println!("\nSupported languages map: {:?}\n", &_localizer.supported_languages);
+            println!("Current working directory: {}\n", _config.runtime_cwd.display());
+
+
+            println!("get_supported_languages() output:");
+            match _localizer.get_supported_languages() {
+                Ok(langs) => {
+                    for lang in langs {
+                        println!(" - {}", lang);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving supported languages: {}", e);
+                }
+            }
+
+            if let Err(e) = Localizer::scan_languages_strict(&_config) {
+                eprintln!("\nStrict scan of the language directory failed: {}", e);
+            }
+
+            for strategy in [VariantStrategy::Index(1), VariantStrategy::Random, VariantStrategy::WeightedRandom] {
+                match _localizer.get_variant("tip", "en-GB", "en-GB", strategy) {
+                    Ok(text) => {
+                        println!("\nBuilt-in variant of 'tip' picked with {:?}: {}", strategy, text);
+                    }
+                    Err(e) => {
+                        eprintln!("Error picking a variant of 'tip': {}", e);
+                    }
+                }
+            }
+
+            match _localizer.language_path("en-GB") {
+                Some(path) => println!("\nFile for 'en-GB': {}", path.display()),
+                None => println!("\n'en-GB' has no backing file."),
+            }
+
+            match _localizer.get_text_by_key("greeting", "en-lm", "en-GB") {
+                Ok(text) => {
+                    println!("\nText for key 'greeting' in 'en-GB': {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving text by key: {}", e);
+                }
+            }
+
+            _localizer.set_scan_ttl(&_config, Some(Duration::from_secs(30)));
+
+            if let Err(e) = _localizer.set_fallback_chain(&["en-US", "en-GB", "fr-XX"]) {
+                eprintln!("\nFallback chain rejected: {}", e);
+            }
+            let _ = _localizer.set_fallback_chain(&["en-US", "en-GB"]);
+
+            match _localizer.get_text_strict("language_name", "en-US") {
+                Ok(text) => {
+                    println!("\nStrict text for key 'language_name' in 'en-US': {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving strict text: {}", e);
+                }
+            }
+
+            for (lang, paths) in _localizer.scan_collisions() {
+                println!("\nLanguage '{}' is provided by {} files.", lang, paths.len());
+            }
+
+            match _localizer.load_language_as("en-AU", _config.language_file_dir_path.join("en-GB.json")) {
+                Ok(()) => {
+                    println!("\nRegistered en-GB.json as 'en-AU'.");
+                }
+                Err(e) => {
+                    eprintln!("Error registering 'en-AU': {}", e);
+                }
+            }
+
+            match _localizer.rescan_languages(&_config) {
+                Ok(_) => {
+                    println!("\nRescanned languages successfully.");
+                }
+                Err(e) => {
+                    eprintln!("Error rescanning languages: {}", e);
+                }
+            }
+
+            match _localizer.reload_language("en-GB") {
+                Ok(_) => {
+                    println!("\nReloaded language 'en-GB' successfully.");
+                }
+                Err(e) => {
+                    eprintln!("Error reloading language 'en-GB': {}", e);
+                }
+            }
+
+            let reload_errors = _localizer.reload_languages(&["en-GB", "en-US", "xx-XX"]);
+            println!("\nReloaded languages with {} error(s).", reload_errors.len());
+            for (lang, e) in &reload_errors {
+                eprintln!(" - {}: {}", lang, e);
+            }
+
+            match _localizer.preload_all() {
+                Ok((loaded, errors)) => {
+                    println!("\nPreloaded {} language(s) with {} error(s).", loaded, errors.len());
+                    for (lang, e) in &errors {
+                        eprintln!(" - {}: {}", lang, e);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error preloading languages: {}", e);
+                }
+            }
+
+            println!("\nCache statistics: {:?}", _localizer.cache_stats());
+            _localizer.reset_stats();
+            println!("Cache statistics after reset: {:?}", _localizer.cache_stats());
+
+            match _localizer.remove_language("de-DE").and_then(|removed| {
+                let text = _localizer.get_text_by_key("farewell", "de-DE", "en-GB")?;
+                _localizer.add_language("de-DE", _config.language_file_dir_path.join("de-DE.json"))?;
+                Ok((removed, text, _localizer.get_text_by_key("farewell", "de-DE", "en-GB")?))
+            }) {
+                Ok((removed, without, with)) => {
+                    println!("\nRemoved 'de-DE': {}. 'farewell' without it: {}, after adding it back: {}", removed, without, with);
+                }
+                Err(e) => {
+                    eprintln!("Error removing and re-adding 'de-DE': {}", e);
+                }
+            }
+
+            match _localizer.reload_all(&_config) {
+                Ok(_) => {
+                    println!("\nReloaded all languages successfully.");
+                }
+                Err(e) => {
+                    eprintln!("Error reloading all languages: {}", e);
+                }
+            }
+
+            match _localizer.with_text("welcome_user", "en-GB", "en-GB", |text| parse_placeholders(text).len()) {
+                Ok(count) => {
+                    println!("\nPlaceholders in 'welcome_user', read in place: {}", count);
+                }
+                Err(e) => {
+                    eprintln!("Error reading 'welcome_user' in place: {}", e);
+                }
+            }
+
+            match _localizer.get_text_with_context("farewell", "button", "en-GB", "en-GB") {
+                Ok(text) => {
+                    println!("\nText for key 'farewell' with context 'button', falling back to the bare key: {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error looking up 'farewell' with context 'button': {}", e);
+                }
+            }
+
+            println!("\nText or key for 'menu.file.open' in 'de-DE': {}", _localizer.get_text_or_key("menu.file.open", "de-DE", "en-GB"));
+            println!("Text or key for 'menu.file.close' in 'de-DE': {}", _localizer.get_text_or_key("menu.file.close", "de-DE", "en-GB"));
+
+            for tag in ["de_de", "DE", "pl"] {
+                match _localizer.get_text_by_key("farewell", tag, "") {
+                    Ok(text) => {
+                        println!("\nText for key 'farewell' requested as '{}': {}", tag, text);
+                    }
+                    Err(e) => {
+                        eprintln!("Error looking up 'farewell' as '{}': {}", tag, e);
+                    }
+                }
+            }
+
+            match _localizer.get_text_by_key("farewell", "en-UN", "en-GB") {
+                Ok(text) => {
+                    println!("\nText for key 'farewell' in 'en-UN' with fallback to 'en-GB': {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving text by key: {}", e);
+                }
+            }
+
+            match _localizer.get_text_by_key("greeting", "de-DE", "en-GB") {
+                Ok(text) => {
+                    println!("\nText for key 'greeting' missing from 'de-DE', falling back to 'en-GB': {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving text with fallback: {}", e);
+                }
+            }
+
+            match _localizer.get_texts(&["greeting", "farewell", "loading", "no_such_key"], "de-DE", "en-GB") {
+                Ok((texts, missing)) => {
+                    println!("\nBatch lookup in 'de-DE': {:?}, missing: {:?}", texts, missing);
+                }
+                Err(e) => {
+                    eprintln!("Error in batch lookup: {}", e);
+                }
+            }
+
+            match _localizer.get_text_by_key_chain("thank_you", &["fr-CA", "ko-KR", "en-US"]) {
+                Ok(text) => {
+                    println!("\nText for key 'thank_you' through the chain fr-CA -> ko-KR -> en-US: {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving text through the chain: {}", e);
+                }
+            }
+
+            match _localizer.get_text_by_key("no_such_key", "en-GB", "en-GB") {
+                Ok(text) => {
+                    println!("\nText for key 'no_such_key': {}", text);
+                }
+                Err(LocalizerError::KeyNotFound { key, lang }) => {
+                    println!("\nMissing translation: '{}' in '{}'", key, lang);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving text by key: {}", e);
+                }
+            }
+
+            match _localizer.get_by_prefix("menu", "en-GB") {
+                Ok(entries) => {
+                    println!("\nEntries under 'menu' in 'en-GB': {:?}", entries);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving entries by prefix: {}", e);
+                }
+            }
+
+            match _localizer.set_key_separator("/").and_then(|_| _localizer.get_text_by_key("menu/file/open", "en-GB", "en-GB")) {
+                Ok(text) => {
+                    println!("\nText for key 'menu/file/open' with separator '{}': {}", _localizer.key_separator(), text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving text with custom separator: {}", e);
+                }
+            }
+            let _ = _localizer.set_key_separator(".");
+
+            match _localizer.get_text_by_key("loading", "en-GB", "en-GB") {
+                Ok(text) => {
+                    println!("\nText for key 'loading' from the built-in default map: {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving built-in text: {}", e);
+                }
+            }
+
+            match _localizer.set_cache_limit(2) {
+                Ok(evicted) => {
+                    println!("\nCache limit set to 2, evicted {} language(s).", evicted);
+                }
+                Err(e) => {
+                    eprintln!("Error setting cache limit: {}", e);
+                }
+            }
+            let _ = _localizer.get_text_by_key("greeting", "en-US", "en-GB");
+            println!("\nTrimmed cache, evicted {} language(s).", _localizer.trim_cache_to(1));
+
+            let memory_source = MemorySource::new()
+                .with_language("ko-KR", &[("greeting", "안녕하세요"), ("farewell", "안녕히 가세요")]);
+            match _localizer.set_source(Box::new(memory_source)).and_then(|_| _localizer.get_text_by_key("greeting", "ko-KR", "en-GB")) {
+                Ok(text) => {
+                    println!("\nText for key 'greeting' in 'ko-KR' from the memory source: {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving text from the memory source: {}", e);
+                }
+            }
+
+            #[cfg(feature = "sqlite")]
+            match _localizer.set_source(Box::new(sqlite_source::SqliteSource::new("./translations.db"))) {
+                Ok(count) => {
+                    println!("\nLanguages provided by the SQLite source: {}", count);
+                }
+                Err(e) => {
+                    eprintln!("Error attaching the SQLite source: {}", e);
+                }
+            }
+
+            let args: HashMap<String, String> = HashMap::from([
+                (String::from("name"), String::from("Tbot")),
+                (String::from("count"), String::from("3")),
+            ]);
+            match _localizer.get_text_with_args("welcome_user", "en-GB", "en-GB", &args) {
+                Ok(text) => {
+                    println!("\nInterpolated text for key 'welcome_user': {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving interpolated text: {}", e);
+                }
+            }
+
+            let unsafe_args: HashMap<String, String> = HashMap::from([
+                (String::from("name"), String::from("<b>O'Brien</b>")),
+                (String::from("count"), String::from("\"1\"")),
+            ]);
+            for mode in [EscapeMode::None, EscapeMode::Html, EscapeMode::Json, EscapeMode::ShellArg] {
+                match _localizer.get_text_with_args_escaped("welcome_user", "en-GB", "en-GB", &unsafe_args, mode) {
+                    Ok(text) => {
+                        println!("\nText for key 'welcome_user' escaped with {:?}: {}", mode, text);
+                    }
+                    Err(e) => {
+                        eprintln!("Error retrieving escaped text: {}", e);
+                    }
+                }
+            }
+
+            let partial_args: HashMap<String, String> = HashMap::from([(String::from("name"), String::from("Tbot"))]);
+            match _localizer.get_text_with_args_checked("welcome_user", "en-GB", "en-GB", &partial_args) {
+                Ok((text, unfilled)) => {
+                    println!("\nText for key 'welcome_user' with unfilled placeholders {:?}: {}", unfilled, text);
+                }
+                Err(e) => {
+                    eprintln!("Error retrieving checked text: {}", e);
+                }
+            }
+            match _localizer.get_text_strict_fmt("welcome_user", "en-GB", "en-GB", &partial_args) {
+                Ok(text) => {
+                    println!("\nStrictly interpolated text for key 'welcome_user': {}", text);
+                }
+                Err(e) => {
+                    eprintln!("\nError rendering 'welcome_user' strictly: {}", e);
+                }
+            }
+
+            _localizer.set_missing_key_behavior(MissingKeyBehavior::KeyAsMessage);
+            match _localizer.get_text_with_args("Goodbye, {name}!", "en-GB", "en-GB", &args) {
+                Ok(text) => {
+                    println!("\nMissing key rendered as its own message: {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error rendering missing key: {}", e);
+                }
+            }
+            _localizer.set_missing_key_behavior(MissingKeyBehavior::ReturnKey);
+            match _localizer.get_text_by_key("not.translated", "en-GB", "en-GB") {
+                Ok(text) => {
+                    println!("\nMissing key returned verbatim: {}", text);
+                }
+                Err(e) => {
+                    eprintln!("Error returning missing key: {}", e);
+                }
+            }
+            _localizer.set_missing_key_behavior(MissingKeyBehavior::Error);
+
+            let key_enum_path = env::temp_dir().join("lang_keys.rs");
+            match codegen::generate_key_enum(_config.language_file_dir_path.join("en-GB.json"), &key_enum_path, "LangKey") {
+                Ok(count) => {
+                    println!("\nGenerated {} key variants into {}", count, key_enum_path.display());
+                }
+                Err(e) => {
+                    eprintln!("Error generating key enum: {}", e);
+                }
+            }
+
+            match _localizer.supported_languages_sorted_by("name") {
+                Ok(langs) => {
+                    println!("\nLanguages sorted by '_meta.name': {:?}", langs);
+                }
+                Err(e) => {
+                    eprintln!("Error sorting languages: {}", e);
+                }
+            }
+
+            for (lang, count) in [("en-GB", 1), ("en-GB", 5), ("pl-PL", 3), ("pl-PL", 5)] {
+                match _localizer.get_plural("new_messages", count, lang, "en-GB") {
+                    Ok(text) => {
+                        println!("\nPlural of 'new_messages' for {} in '{}': {}", count, lang, text);
+                    }
+                    Err(e) => {
+                        eprintln!("Error looking up a plural of 'new_messages': {}", e);
+                    }
+                }
+            }
+
+            #[cfg(feature = "binary-cache")]
+            {
+                let cache_path = env::temp_dir().join("localizer_cache.bin");
+                match _localizer.save_binary_cache(&cache_path).and_then(|_| _localizer.load_binary_cache(&cache_path)) {
+                    Ok(count) => {
+                        println!("\nLanguages restored from the binary cache: {}", count);
+                    }
+                    Err(e) => {
+                        eprintln!("Error using the binary cache: {}", e);
+                    }
+                }
+            }
+
+            #[cfg(feature = "async")]
+            {
+                _localizer.evict_language("en-US");
+                let runtime = tokio::runtime::Builder::new_current_thread().build();
+                match runtime.map_err(LocalizerError::from).and_then(|runtime| runtime.block_on(_localizer.get_text_by_key_async("greeting", "en-US", "en-GB"))) {
+                    Ok(text) => {
+                        println!("\nText for key 'greeting' in 'en-US', loaded asynchronously: {}", text);
+                    }
+                    Err(e) => {
+                        eprintln!("Error in async lookup: {}", e);
+                    }
+                }
+            }
+
+            #[cfg(feature = "watch")]
+            {
+                let watch_dir = env::temp_dir().join("localizer_watch");
+                let _ = fs::create_dir_all(&watch_dir);
+                let _ = fs::remove_file(watch_dir.join("fr-FR.json"));
+                match Localizer::with_config(&watch_dir, "json").and_then(|(mut watched, config)| {
+                    let _watcher = watched.watch(&config)?;
+                    fs::write(watch_dir.join("fr-FR.json"), r#"{"greeting": "Bonjour"}"#)?;
+                    std::thread::sleep(Duration::from_millis(200));
+                    watched.get_text_by_key("greeting", "fr-FR", "fr-FR")
+                }) {
+                    Ok(text) => {
+                        println!("\nText for 'greeting' in 'fr-FR', picked up by the watcher: {}", text);
+                    }
+                    Err(e) => {
+                        eprintln!("Error watching language files: {}", e);
+                    }
+                }
+            }
+
+            match _localizer.overall_coverage("en-GB") {
+                Ok(coverage) => {
+                    println!("\nOverall coverage against 'en-GB': {:.1}%", coverage);
+                }
+                Err(e) => {
+                    eprintln!("Error computing coverage: {}", e);
+                }
+            }
+
+            match _localizer.prepare_keys("en-GB", &["greeting", "farewell", "missing"]) {
+                Ok(handles) => {
+                    let texts: Vec<Option<&str>> = handles.iter().map(|handle| _localizer.get_by_handle(*handle)).collect();
+                    println!("\nTexts resolved through key handles: {:?}", texts);
+                    let _ = _localizer.reload_language("en-GB");
+                    println!("Handle after reloading 'en-GB': {:?}", _localizer.get_by_handle(handles[0]));
+                }
+                Err(e) => {
+                    eprintln!("Error preparing key handles: {}", e);
+                }
+            }
+
+            match _localizer.validate_against("en-GB").and_then(|missing| Ok((missing, _localizer.extra_keys_against("en-GB")?))) {
+                Ok((missing, extra)) => {
+                    let mut langs: Vec<&String> = missing.keys().chain(extra.keys()).collect();
+                    langs.sort();
+                    langs.dedup();
+                    for lang in langs {
+                        println!("\nKeys of 'en-GB' missing in '{}': {:?}", lang, missing.get(lang).map(Vec::len).unwrap_or(0));
+                        println!("Keys in '{}' that 'en-GB' lacks: {:?}", lang, extra.get(lang).cloned().unwrap_or_default());
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error validating languages against 'en-GB': {}", e);
+                }
+            }
+
+            match _localizer.export_language("de-DE").and_then(|own| Ok((own, _localizer.export_merged("de-DE", "en-GB")?))) {
+                Ok((own, merged)) => {
+                    println!("\nExported 'de-DE' ({} lines):\n{}", own.lines().count(), own);
+                    println!("Exported 'de-DE' merged over 'en-GB': {} lines", merged.lines().count());
+                }
+                Err(e) => {
+                    eprintln!("Error exporting 'de-DE': {}", e);
+                }
+            }
+
+            match _localizer.placeholders_in_language("en-GB") {
+                Ok(names) => {
+                    println!("\nPlaceholders used in 'en-GB': {:?}", names);
+                }
+                Err(e) => {
+                    eprintln!("Error collecting placeholders: {}", e);
+                }
+            }
+
+            let shared = SharedLocalizer::new(_localizer);
+            std::thread::scope(|scope| {
+                for (i, lang) in ["en-GB", "en-US", "de-DE", "en-GB"].into_iter().enumerate() {
+                    let shared = &shared;
+                    scope.spawn(move || {
+                        let args: HashMap<String, String> = HashMap::from([
+                            (String::from("name"), format!("thread {}", i)),
+                            (String::from("count"), String::from("0")),
+                        ]);
+                        for _ in 0..100 {
+                            let result = shared.get_text_by_key("farewell", lang, "en-GB")
+                                .and(shared.get_text_with_args("welcome_user", lang, "en-GB", &args))
+                                .and(shared.get_texts(&["greeting", "farewell"], lang, "en-GB"))
+                                .and(shared.with_text("thank_you", lang, "en-GB", str::len));
+                            if let Err(e) = result {
+                                eprintln!("Thread {} failed a shared lookup: {}", i, e);
+                                return;
+                            }
+                        }
+                        println!("\nThread {} resolved its texts in '{}' 100 times.", i, lang);
+                    });
+                }
+            });
+        }
+        Err(e) => {
             eprintln!("Failed to initialize Localizer: {}", e);
             eprintln!("\n\n치명적 오류 발생! 프로그램을 종료합니다.\n\n");
             std::process::exit(1);
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh directory per test under the system temp dir, holding `files` as (name, contents).
+    fn language_dir(test: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("localizer_test_{}_{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in files {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn custom_key_separator_joins_nested_keys() -> Result<()> {
+        let dir = language_dir("key_separator", &[("en-GB.json", r#"{"menu": {"file": {"open": "Open", "save": "Save"}}}"#)]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+        localizer.set_key_separator("/")?;
+
+        assert_eq!(localizer.get_text_by_key("menu/file/open", "en-GB", "en-GB")?, "Open");
+        let mut keys: Vec<String> = localizer.get_by_prefix("menu", "en-GB")?.into_keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["menu/file/open", "menu/file/save"]);
+        Ok(())
+    }
+
+    #[test]
+    fn variants_and_weights_come_from_one_language() -> Result<()> {
+        let dir = language_dir("variants", &[
+            ("en-GB.json", r#"{"tip.0": "en 0", "tip.1": "en 1", "tip.1.weight": "0", "tip.2": "en 2"}"#),
+            ("de-DE.json", r#"{"tip.0": "de 0", "tip.1.weight": "5"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.get_variant("tip", "de-DE", "en-GB", VariantStrategy::Index(1))?, "de 0");
+        assert_eq!(localizer.get_variant("tip", "en-GB", "en-GB", VariantStrategy::Index(2))?, "en 2");
+        // de-DE's stray tip.1.weight must not pull in en-GB's tip.1.
+        for _ in 0..20 {
+            assert_eq!(localizer.get_variant("tip", "de-DE", "en-GB", VariantStrategy::WeightedRandom)?, "de 0");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn variant_weights_are_not_translatable_keys() -> Result<()> {
+        let dir = language_dir("variant_weights", &[
+            ("en-GB.json", r#"{"tip.0": "Tip", "tip.0.weight": "2"}"#),
+            ("de-DE.json", r#"{"tip.0": "Tipp"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert!(localizer.validate_against("en-GB")?.is_empty());
+        assert_eq!(localizer.overall_coverage("en-GB")?, 100.0);
+        Ok(())
+    }
+
+    #[test]
+    fn prepare_keys_reuses_tables_and_drops_them_on_reload() -> Result<()> {
+        let dir = language_dir("handles", &[
+            ("en-GB.json", r#"{"greeting": "Hello"}"#),
+            ("de-DE.json", r#"{"greeting": "Hallo"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+        localizer.set_cache_limit(1)?;
+
+        let handles = localizer.prepare_keys("en-GB", &["greeting"])?;
+        assert_eq!(localizer.prepare_keys("en-GB", &["greeting"])?, handles);
+        assert_eq!(localizer.handle_tables.len(), 1);
+
+        // Evicting en-GB keeps the copied values; reading the file again is what retires them.
+        localizer.get_text_strict("greeting", "de-DE")?;
+        assert_eq!(localizer.get_by_handle(handles[0]), Some("Hello"));
+        fs::write(dir.join("en-GB.json"), r#"{"greeting": "Hi"}"#).unwrap();
+        localizer.get_text_strict("greeting", "en-GB")?;
+        assert_eq!(localizer.get_by_handle(handles[0]), None);
+
+        let fresh = localizer.prepare_keys("en-GB", &["greeting"])?;
+        assert_eq!(localizer.get_by_handle(fresh[0]), Some("Hi"));
+        assert_eq!(localizer.handle_tables.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn scalars_and_arrays_are_flattened_to_text() -> Result<()> {
+        let dir = language_dir("scalars", &[
+            ("en-GB.json", r#"{"unread_limit": 99, "beta": true, "weekdays": ["Monday", "Tuesday", "Wednesday"], "unset": null}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.get_text_by_key("unread_limit", "en-GB", "en-GB")?, "99");
+        assert_eq!(localizer.get_text_by_key("beta", "en-GB", "en-GB")?, "true");
+        assert_eq!(localizer.get_text_by_key("weekdays.2", "en-GB", "en-GB")?, "Wednesday");
+        assert!(localizer.get_text_by_key("unset", "en-GB", "en-GB").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn context_falls_back_to_the_bare_key_per_language() -> Result<()> {
+        let dir = language_dir("context", &[
+            ("en-GB.json", r#"{"greeting": "Hello", "greeting@formal": "Good day", "greeting@casual": "Hi"}"#),
+            ("de-DE.json", r#"{"greeting": "Hallo"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.get_text_with_context("greeting", "formal", "en-GB", "en-GB")?, "Good day");
+        assert_eq!(localizer.get_text_with_context("greeting", "shouted", "en-GB", "en-GB")?, "Hello");
+        assert_eq!(localizer.get_text_with_context("greeting", "casual", "de-DE", "en-GB")?, "Hallo");
+        assert_eq!(localizer.get_text_with_context("greeting", "", "en-GB", "en-GB")?, "Hello");
+        Ok(())
+    }
+
+    #[test]
+    fn plural_picks_the_category_and_falls_back_to_other() -> Result<()> {
+        let dir = language_dir("plurals", &[
+            ("en-GB.json", r#"{"new_messages": {"one": "{count} message", "other": "{count} messages"}}"#),
+            ("pl-PL.json", r#"{"new_messages": {"one": "{count} wiadomość", "few": "{count} wiadomości"}}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.get_plural("new_messages", 1, "en-GB", "en-GB")?, "1 message");
+        assert_eq!(localizer.get_plural("new_messages", 5, "en-GB", "en-GB")?, "5 messages");
+        assert_eq!(localizer.get_plural("new_messages", 3, "pl-PL", "en-GB")?, "3 wiadomości");
+        // pl-PL has no "many" or "other", so 5 falls through to en-GB's "other".
+        assert_eq!(localizer.get_plural("new_messages", 5, "pl-PL", "en-GB")?, "5 messages");
+        Ok(())
+    }
+
+    #[test]
+    fn missing_key_falls_back_to_the_fallback_language() -> Result<()> {
+        let dir = language_dir("fallback", &[
+            ("de-DE.json", r#"{"farewell": "Auf Wiedersehen"}"#),
+            ("en-GB.json", r#"{"greeting": "Hello", "farewell": "Goodbye"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.get_text_by_key("greeting", "de-DE", "en-GB")?, "Hello");
+        assert_eq!(localizer.get_text_by_key("farewell", "de-DE", "en-GB")?, "Auf Wiedersehen");
+        Ok(())
+    }
+
+    #[test]
+    fn shared_localizer_answers_from_many_threads() -> Result<()> {
+        let dir = language_dir("shared", &[
+            ("en-GB.json", r#"{"farewell": "Goodbye"}"#),
+            ("en-US.json", r#"{"farewell": "Bye"}"#),
+            ("de-DE.json", r#"{"greeting": "Hallo"}"#),
+        ]);
+        let (localizer, _) = Localizer::with_config(&dir, "json")?;
+        let shared = SharedLocalizer::new(localizer);
+
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = [("en-GB", "Goodbye"), ("en-US", "Bye"), ("de-DE", "Goodbye"), ("en-GB", "Goodbye")]
+                .into_iter()
+                .map(|(lang, expected)| {
+                    let shared = &shared;
+                    scope.spawn(move || {
+                        for _ in 0..100 {
+                            assert_eq!(shared.get_text_by_key("farewell", lang, "en-GB").unwrap(), expected);
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                worker.join().unwrap();
+            }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn exact_language_apis_resolve_tags() -> Result<()> {
+        let dir = language_dir("resolve_tags", &[
+            ("en-GB.json", r#"{"greeting": "Hello {name}", "menu": {"open": "Open"}}"#),
+            ("de-DE.json", r#"{"greeting": "Hallo {name}"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.get_text_strict("greeting", "en_gb")?, "Hello {name}");
+        assert_eq!(localizer.get_by_prefix("menu", "EN-gb")?.len(), 1);
+        assert!(localizer.placeholders_in_language("de_DE")?.contains("name"));
+        assert_eq!(localizer.language_path("de"), Some(dir.join("de-DE.json").as_path()));
+        let handles = localizer.prepare_keys("en_GB", &["greeting"])?;
+        assert_eq!(localizer.get_by_handle(handles[0]), Some("Hello {name}"));
+        assert_eq!(localizer.prepare_keys("en-GB", &["greeting"])?, handles);
+
+        localizer.set_fallback_chain(&["de"])?;
+        assert_eq!(localizer.get_text_by_key("greeting", "xx-XX", "xx-XX")?, "Hallo {name}");
+        assert!(localizer.set_fallback_chain(&["xx"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn preload_all_keeps_the_cache_limit() -> Result<()> {
+        let dir = language_dir("preload_all", &[
+            ("en-GB.json", r#"{"greeting": "Hello"}"#),
+            ("de-DE.json", r#"{"greeting": "Hallo"}"#),
+            ("nl-NL.json", r#"{"greeting": "Hallo"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+        localizer.set_cache_limit(1)?;
+
+        let (loaded, errors) = localizer.preload_all()?;
+        assert_eq!((loaded, errors.len()), (3, 0));
+        let stats = localizer.cache_stats();
+        assert_eq!((stats.cached_languages, stats.limit), (3, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn regional_language_layers_over_its_base_before_the_fallback() -> Result<()> {
+        let dir = language_dir("layered", &[
+            ("en.json", r#"{"greeting": "Hello", "colour": "Colour"}"#),
+            ("en-US.json", r#"{"colour": "Color"}"#),
+            ("en-GB.json", r#"{"greeting": "Good day", "colour": "Colour (GB)"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.get_text_by_key("colour", "en-US", "en-GB")?, "Color");
+        assert_eq!(localizer.get_text_by_key("greeting", "en-US", "en-GB")?, "Hello");
+        Ok(())
+    }
+
+    #[test]
+    fn manual_registrations_use_canonical_codes() -> Result<()> {
+        let dir = language_dir("manual", &[("en-GB.json", r#"{"greeting": "Hello"}"#)]);
+        let extra = language_dir("manual_extra", &[
+            ("nl.json", r#"{"greeting": "Hallo"}"#),
+            ("sv.json", r#"{"greeting": "Hej"}"#),
+        ]);
+        let (mut localizer, config) = Localizer::with_config(&dir, "json")?;
+
+        localizer.add_language("nl_nl", extra.join("nl.json"))?;
+        localizer.load_language_as("sv_se", extra.join("sv.json"))?;
+        let mut langs = localizer.get_supported_languages()?;
+        langs.sort();
+        assert_eq!(langs, ["en-GB", "nl-NL", "sv-SE"]);
+
+        localizer.rescan_languages(&config)?;
+        assert_eq!(localizer.get_supported_languages()?, vec![String::from("en-GB")]);
+        Ok(())
+    }
+
+    #[test]
+    fn rescan_evicts_only_changed_languages() -> Result<()> {
+        let dir = language_dir("rescan", &[
+            ("nl-NL.json", r#"{"greeting": "Hallo"}"#),
+            ("sv-SE.json", r#"{"greeting": "Hej"}"#),
+        ]);
+        let (mut localizer, config) = Localizer::with_config(&dir, "json")?;
+        let sv_file = dir.join("sv-SE.json");
+        let scanned = fs::metadata(&sv_file)?.modified()?;
+        let edit = |text: &str, modified: SystemTime| -> Result<()> {
+            fs::write(&sv_file, text)?;
+            fs::File::options().write(true).open(&sv_file)?.set_modified(modified)?;
+            Ok(())
+        };
+
+        // Edited within the same mtime tick: only the length gives it away.
+        localizer.preload(&["nl-NL", "sv-SE"]);
+        edit(r#"{"greeting": "Hej hej"}"#, scanned)?;
+        localizer.rescan_languages(&config)?;
+        assert!(localizer.supported_languages_cache.contains_key("nl-NL"));
+        assert!(!localizer.supported_languages_cache.contains_key("sv-SE"));
+        assert_eq!(localizer.get_text_by_key("greeting", "sv-SE", "")?, "Hej hej");
+
+        // Same length, newer mtime.
+        edit(r#"{"greeting": "Hej hoj"}"#, scanned + Duration::from_secs(5))?;
+        localizer.rescan_languages(&config)?;
+        assert!(localizer.supported_languages_cache.contains_key("nl-NL"));
+        assert!(!localizer.supported_languages_cache.contains_key("sv-SE"));
+        assert_eq!(localizer.get_text_by_key("greeting", "sv-SE", "")?, "Hej hoj");
+        Ok(())
+    }
+
+    #[test]
+    fn with_text_reads_languages_then_the_default_map() -> Result<()> {
+        let dir = language_dir("with_text", &[
+            ("en-GB.json", r#"{"greeting": "Hello"}"#),
+            ("de-DE.json", r#"{"farewell": "Auf Wiedersehen"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+        localizer.set_default_map(&[("app_name", "Playground")]);
+
+        assert_eq!(localizer.with_text("greeting", "de-DE", "en-GB", str::len)?, 5);
+        assert_eq!(localizer.with_text("app_name", "de-DE", "en-GB", str::to_string)?, "Playground");
+        assert!(matches!(localizer.with_text("missing", "de-DE", "en-GB", str::len), Err(LocalizerError::KeyNotFound { .. })));
+        localizer.set_missing_key_behavior(MissingKeyBehavior::KeyAsMessage);
+        assert_eq!(localizer.with_text("missing", "de-DE", "en-GB", str::to_string)?, "missing");
+        Ok(())
+    }
+
+    #[test]
+    fn shared_read_hits_keep_languages_recent() -> Result<()> {
+        let dir = language_dir("shared_lru", &[
+            ("en-GB.json", r#"{"greeting": "Hello {name}"}"#),
+            ("de-DE.json", r#"{"greeting": "Hallo {name}"}"#),
+            ("fr-FR.json", r#"{"greeting": "Bonjour {name}"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+        localizer.set_cache_limit(2)?;
+        let shared = SharedLocalizer::new(localizer);
+        shared.get_text_by_key("greeting", "en-GB", "en-GB")?;
+        shared.get_text_by_key("greeting", "de-DE", "de-DE")?;
+        let hits_before = shared.write().cache_stats().hits;
+
+        let args = HashMap::from([(String::from("name"), String::from("Ada"))]);
+        for _ in 0..250 {
+            assert_eq!(shared.get_text_by_key("greeting", "en-GB", "en-GB")?, "Hello {name}");
+            assert_eq!(shared.get_text_with_args("greeting", "en-GB", "en-GB", &args)?, "Hello Ada");
+            assert_eq!(shared.get_texts(&["greeting", "missing"], "en-GB", "en-GB")?.1, ["missing"]);
+            assert_eq!(shared.with_text("greeting", "en-GB", "en-GB", str::len)?, 12);
+        }
+        shared.get_text_by_key("greeting", "fr-FR", "fr-FR")?;
+
+        let localizer = shared.write();
+        assert!(localizer.supported_languages_cache.contains_key("en-GB"));
+        assert!(!localizer.supported_languages_cache.contains_key("de-DE"));
+        assert_eq!(localizer.cache_stats().hits - hits_before, 1000);
+        Ok(())
+    }
+
+    #[test]
+    fn concurrent_cold_lookups_load_a_language_once() -> Result<()> {
+        let dir = language_dir("single_flight", &[("en-GB.json", r#"{"greeting": "Hello"}"#)]);
+        let (localizer, _) = Localizer::with_config(&dir, "json")?;
+        let shared = SharedLocalizer::new(localizer);
+        let barrier = std::sync::Barrier::new(8);
+
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..8).map(|_| scope.spawn(|| {
+                barrier.wait();
+                shared.get_text_by_key("greeting", "en-GB", "en-GB").unwrap()
+            })).collect();
+            for worker in workers {
+                assert_eq!(worker.join().unwrap(), "Hello");
+            }
+        });
+        let stats = shared.write().cache_stats();
+        assert_eq!((stats.misses, stats.hits), (1, 7));
+        Ok(())
+    }
+
+    #[test]
+    fn plural_category_follows_the_language_holding_the_text() -> Result<()> {
+        let dir = language_dir("plural_fallback", &[
+            ("en-GB.json", r#"{"items": {"one": "{count} item", "other": "{count} items"}}"#),
+            ("fr-FR.json", r#"{"other_key": "Autre"}"#),
+            ("ru-RU.json", r#"{"other_key": "Другой"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.get_plural("items", 0, "fr-FR", "en-GB")?, "0 items");
+        assert_eq!(localizer.get_plural("items", 21, "ru-RU", "en-GB")?, "21 items");
+        assert_eq!(localizer.get_plural("items", 1, "ru-RU", "en-GB")?, "1 item");
+        Ok(())
+    }
+
+    #[test]
+    fn weighted_variants_skip_zero_weights_unless_all_are_zero() -> Result<()> {
+        let dir = language_dir("weighted_variants", &[
+            ("en-GB.json", r#"{"tip": ["Rare", "Common"], "tip.0.weight": 0}"#),
+            ("de-DE.json", r#"{"tip": ["Eins", "Zwei"], "tip.0.weight": 0, "tip.1.weight": "0"}"#),
+            ("fr-FR.json", r#"{"tip": ["Un"], "tip.0.weight": "-1"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        for _ in 0..20 {
+            assert_eq!(localizer.get_variant("tip", "en-GB", "en-GB", VariantStrategy::WeightedRandom)?, "Common");
+            let any = localizer.get_variant("tip", "de-DE", "de-DE", VariantStrategy::WeightedRandom)?;
+            assert!(any == "Eins" || any == "Zwei");
+        }
+        assert!(matches!(localizer.get_variant("tip", "fr-FR", "fr-FR", VariantStrategy::WeightedRandom), Err(LocalizerError::ParseError { .. })));
+        Ok(())
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_catalogs_flatten_and_json_wins_a_shared_code() -> Result<()> {
+        let dir = language_dir("yaml", &[
+            ("es-ES.json", r#"{"greeting": "Hola (JSON)"}"#),
+            ("es-ES.yaml", "greeting: Hola (YAML)\n"),
+            ("it-IT.yml", "greeting: \"Ciao\"\nmenu:\n  open: Apri\n"),
+        ]);
+        let (mut localizer, config) = Localizer::builder().language_dir(&dir).extensions(&["json", "yaml", "yml"]).build()?;
+
+        assert_eq!(localizer.get_text_by_key("greeting", "es-ES", "es-ES")?, "Hola (JSON)");
+        assert_eq!(localizer.get_text_by_key("menu.open", "it-IT", "it-IT")?, "Apri");
+        assert_eq!(localizer.scan_collisions().len(), 1);
+        assert!(matches!(Localizer::scan_languages_strict(&config), Err(LocalizerError::DuplicateLanguages(_))));
+        Ok(())
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_catalogs_flatten_like_json() -> Result<()> {
+        let dir = language_dir("toml", &[("pt-PT.toml", "greeting = \"Olá\"\n\n[menu]\nopen = \"Abrir\"\n")]);
+        let (mut localizer, _) = Localizer::builder().language_dir(&dir).extensions(&["toml"]).build()?;
+
+        assert_eq!(localizer.get_text_by_key("greeting", "pt-PT", "pt-PT")?, "Olá");
+        assert_eq!(localizer.get_text_by_key("menu.open", "pt-PT", "pt-PT")?, "Abrir");
+        Ok(())
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    #[test]
+    fn yaml_files_need_the_yaml_feature() -> Result<()> {
+        let dir = language_dir("yaml_disabled", &[("it-IT.yml", "greeting: Ciao\n")]);
+        let (mut localizer, _) = Localizer::builder().language_dir(&dir).extensions(&["yml"]).build()?;
+
+        assert!(matches!(localizer.get_text_by_key("greeting", "it-IT", "it-IT"), Err(LocalizerError::ParseError { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn plural_forms_compare_by_base_key() -> Result<()> {
+        let dir = language_dir("plural_keys", &[
+            ("en-GB.json", r#"{"new_messages": {"one": "{count} message", "other": "{count} messages"}, "farewell": "Goodbye"}"#),
+            ("pl-PL.json", r#"{"new_messages": {"one": "{count} wiadomość", "few": "{count} wiadomości", "many": "{count} wiadomości"}}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.validate_against("en-GB")?, HashMap::from([(String::from("pl-PL"), vec![String::from("farewell")])]));
+        assert!(localizer.extra_keys_against("en-GB")?.is_empty());
+        assert_eq!(localizer.overall_coverage("en-GB")?, 50.0);
+        Ok(())
+    }
+
+    #[test]
+    fn reference_languages_resolve_tags() -> Result<()> {
+        let dir = language_dir("reference_tags", &[
+            ("en-GB.json", r#"{"greeting": "Hello", "farewell": "Goodbye"}"#),
+            ("de-DE.json", r#"{"greeting": "Hallo", "extra": "Mehr"}"#),
+        ]);
+        let (mut localizer, _) = Localizer::with_config(&dir, "json")?;
+
+        assert_eq!(localizer.validate_against("en_gb")?, HashMap::from([(String::from("de-DE"), vec![String::from("farewell")])]));
+        assert_eq!(localizer.extra_keys_against("EN-gb")?, HashMap::from([(String::from("de-DE"), vec![String::from("extra")])]));
+        assert_eq!(localizer.overall_coverage("en_gb")?, 50.0);
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::error::Result;
+use crate::{Localizer, interpolate};
+
+// A Localizer that can be shared across threads (e.g. in an Arc handed to web handlers).
+//
+// Lookups whose languages are already cached run under the read lock, so they proceed
+// concurrently. Only a lookup that has to load a language (or rescan, or report a miss) takes
+// the write lock. A thread that waited on the write lock re-checks the cache once it gets it,
+// so when several threads miss the same cold language at once only the first one reads and
+// parses the file; the others find it cached. This de-duplication only exists here, not on a
+// plain Localizer, which needs &mut self for every lookup anyway.
+//
+// Hits under the read lock are noted in `read_hits` and applied to the LRU order and the hit
+// count the next time the write lock is taken, so languages in heavy use are not evicted first.
+pub struct SharedLocalizer {
+    inner: RwLock<Localizer>,
+    // Languages used by read-lock lookups, least recently used first, with how often.
+    read_hits: Mutex<Vec<(String, u64)>>,
+}
+
+// Fails to build if a field (e.g. a feature-gated watcher or source) stops the Localizer from
+// being shared across threads.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Localizer>();
+    assert_send_sync::<SharedLocalizer>();
+};
+
+impl SharedLocalizer {
+    // Private Functions
+    fn read(&self) -> RwLockReadGuard<'_, Localizer> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Called with the read lock still held, so a writer that follows sees these hits.
+    fn note_hits(&self, used: Vec<String>) {
+        let mut read_hits = self.read_hits.lock().unwrap_or_else(PoisonError::into_inner);
+        for lang in used {
+            let count = match read_hits.iter().position(|(l, _)| *l == lang) {
+                Some(i) => read_hits.remove(i).1,
+                None => 0,
+            };
+            read_hits.push((lang, count + 1));
+        }
+    }
+
+    // Public Functions
+    pub fn new(localizer: Localizer) -> SharedLocalizer {
+        SharedLocalizer { inner: RwLock::new(localizer), read_hits: Mutex::new(Vec::new()) }
+    }
+
+    pub fn get_text_by_key(&self, _key: &str, _lang: &str, fallback: &str) -> Result<String> {
+        {
+            let localizer = self.read();
+            let mut used: Vec<String> = Vec::new();
+            if let Some(text) = localizer.peek_text(_key, _lang, fallback, &mut used) {
+                let text = text.clone();
+                self.note_hits(used);
+                return Ok(text);
+            }
+        }
+        self.write().get_text_by_key(_key, _lang, fallback)
+    }
+
+    pub fn get_text_with_args(&self, _key: &str, _lang: &str, fallback: &str, args: &HashMap<String, String>) -> Result<String> {
+        {
+            let localizer = self.read();
+            let mut used: Vec<String> = Vec::new();
+            if let Some(template) = localizer.peek_text(_key, _lang, fallback, &mut used) {
+                let text = interpolate(template, args);
+                self.note_hits(used);
+                return Ok(text);
+            }
+        }
+        self.write().get_text_with_args(_key, _lang, fallback, args)
+    }
+
+    pub fn get_texts(&self, keys: &[&str], _lang: &str, fallback: &str) -> Result<(HashMap<String, String>, Vec<String>)> {
+        {
+            let localizer = self.read();
+            let mut used: Vec<String> = Vec::new();
+            if let Some(found) = localizer.peek_texts(keys, _lang, fallback, &mut used) {
+                self.note_hits(used);
+                return Ok(found);
+            }
+        }
+        self.write().get_texts(keys, _lang, fallback)
+    }
+
+    /// Like Localizer::with_text; under the read lock `f` sees the cached value in place.
+    pub fn with_text<R>(&self, _key: &str, _lang: &str, fallback: &str, f: impl FnOnce(&str) -> R) -> Result<R> {
+        {
+            let localizer = self.read();
+            let mut used: Vec<String> = Vec::new();
+            if let Some(text) = localizer.peek_text(_key, _lang, fallback, &mut used) {
+                let result = f(text);
+                self.note_hits(used);
+                return Ok(result);
+            }
+        }
+        self.write().with_text(_key, _lang, fallback, f)
+    }
+
+    /// Exclusive access for everything else (reloads, configuration, plurals, ...). Hits
+    /// recorded under the read lock are applied first.
+    pub fn write(&self) -> RwLockWriteGuard<'_, Localizer> {
+        let mut localizer = self.inner.write().unwrap_or_else(PoisonError::into_inner);
+        let hits = std::mem::take(&mut *self.read_hits.lock().unwrap_or_else(PoisonError::into_inner));
+        localizer.apply_read_hits(hits);
+        localizer
+    }
+}
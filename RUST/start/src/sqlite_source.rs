@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+
+use crate::LanguageSource;
+use crate::error::{LocalizerError, Result};
+
+// Reads translations from a SQLite table with one row per (lang, key):
+//
+//     CREATE TABLE translations (
+//         lang  TEXT NOT NULL,
+//         key   TEXT NOT NULL,
+//         value TEXT NOT NULL,
+//         PRIMARY KEY (lang, key)
+//     );
+//
+// The database is opened for each query, so the file can be replaced by the CMS
+// while the Localizer is running. Keys are used as stored; no flattening is applied.
+pub struct SqliteSource {
+    db_path: PathBuf,
+    table: String,
+}
+
+fn sqlite_error(e: rusqlite::Error) -> LocalizerError {
+    LocalizerError::Io(std::io::Error::other(format!("SQLite error: {}", e)))
+}
+
+impl SqliteSource {
+    pub fn new(db_path: impl Into<PathBuf>) -> SqliteSource {
+        SqliteSource {
+            db_path: db_path.into(),
+            table: String::from("translations"),
+        }
+    }
+
+    fn open(&self) -> Result<Connection> {
+        if !self.db_path.is_file() {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, format!("SQLite database not found: {}", self.db_path.display())).into());
+        }
+        Connection::open(&self.db_path).map_err(sqlite_error)
+    }
+}
+
+impl LanguageSource for SqliteSource {
+    fn languages(&self) -> Result<Vec<String>> {
+        let conn = self.open()?;
+        let mut stmt = conn.prepare(&format!("SELECT DISTINCT lang FROM {}", self.table)).map_err(sqlite_error)?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0)).map_err(sqlite_error)?;
+        rows.collect::<std::result::Result<Vec<String>, _>>().map_err(sqlite_error)
+    }
+
+    fn load_language(&self, lang: &str) -> Result<HashMap<String, String>> {
+        let conn = self.open()?;
+        let mut stmt = conn.prepare(&format!("SELECT key, value FROM {} WHERE lang = ?1", self.table)).map_err(sqlite_error)?;
+        let rows = stmt.query_map([lang], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))).map_err(sqlite_error)?;
+        let lang_map: HashMap<String, String> = rows.collect::<std::result::Result<HashMap<String, String>, _>>().map_err(sqlite_error)?;
+        if lang_map.is_empty() {
+            return Err(LocalizerError::LanguageNotFound(lang.to_string()));
+        }
+        Ok(lang_map)
+    }
+}
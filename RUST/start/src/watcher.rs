@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error::{LocalizerError, Result};
+use crate::{Config, Localizer, LogLevel, canonical_lang_code, extension_rank, file_stamp};
+
+// Paths (or watch errors) reported by the watcher thread, applied by the Localizer on its next
+// lookup. Kept behind a Mutex (not an mpsc Receiver) so the Localizer stays Sync for SharedLocalizer.
+pub(crate) struct WatchState {
+    pending: Arc<Mutex<Vec<notify::Result<PathBuf>>>>,
+    config: Config,
+}
+
+impl WatchState {
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.lock().unwrap_or_else(PoisonError::into_inner).is_empty()
+    }
+}
+
+// Keeps the watch alive; dropping it stops new changes from being picked up.
+pub struct LanguageWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+fn notify_error(e: notify::Error) -> LocalizerError {
+    LocalizerError::Io(std::io::Error::other(format!("File watcher error: {}", e)))
+}
+
+impl Localizer {
+    // Private Functions
+    // Each changed path is checked against the disk rather than trusting the event kind, so
+    // renames and editors that save via a temporary file end up in the right state.
+    pub(crate) fn apply_watch_events(&mut self) -> Result<()> {
+        let Some(state) = self.watch_state.as_ref() else { return Ok(()) };
+        let paths: Vec<notify::Result<PathBuf>> = std::mem::take(&mut *state.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let config = state.config.clone();
+
+        for path in paths {
+            let path = match path {
+                Ok(path) => path,
+                Err(e) => {
+                    self.log(LogLevel::Error, &format!("Error watching language files: {}", e));
+                    continue;
+                }
+            };
+            if extension_rank(&config, &path).is_none() {
+                continue;
+            }
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+            let lang = canonical_lang_code(stem);
+
+            // Several files for one language: let a full rescan pick the winner.
+            if self.scan_collisions.iter().any(|(l, _)| *l == lang)
+                || self.supported_languages.get(&lang).is_some_and(|p| *p != path && path.is_file()) {
+                self.rescan_languages(&config)?;
+                continue;
+            }
+
+            // Keep scanned_stamps in step so the next rescan does not count this change again.
+            if path.is_file() {
+                match file_stamp(&path) {
+                    Some(stamp) => self.scanned_stamps.insert(lang.clone(), stamp),
+                    None => self.scanned_stamps.remove(&lang),
+                };
+                self.supported_languages.insert(lang.clone(), path);
+            } else if self.supported_languages.get(&lang) == Some(&path) {
+                self.supported_languages.remove(&lang);
+                self.scanned_stamps.remove(&lang);
+            }
+            self.evict_language(&lang);
+        }
+        Ok(())
+    }
+
+    // Public Functions
+    /// Watches `config`'s language directory and applies created, modified and deleted files
+    /// on the next lookup, without a full rescan. Changes stop being picked up once the
+    /// returned LanguageWatcher is dropped.
+    pub fn watch(&mut self, config: &Config) -> Result<LanguageWatcher> {
+        let pending: Arc<Mutex<Vec<notify::Result<PathBuf>>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&pending);
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                sink.lock().unwrap_or_else(PoisonError::into_inner).extend(event.paths.into_iter().map(Ok));
+            }
+            Ok(_) => {}
+            Err(e) => sink.lock().unwrap_or_else(PoisonError::into_inner).push(Err(e)),
+        }).map_err(notify_error)?;
+
+        watcher.watch(&config.language_file_dir_path, RecursiveMode::NonRecursive)
+            .map_err(notify_error)?;
+
+        self.watch_state = Some(WatchState { pending, config: config.clone() });
+        Ok(LanguageWatcher { _watcher: watcher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime};
+
+    use super::WatchState;
+    use crate::Localizer;
+    use crate::error::Result;
+
+    #[test]
+    fn applied_changes_are_not_evicted_again_by_a_rescan() -> Result<()> {
+        let dir = env::temp_dir().join(format!("localizer_test_{}_watch", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let file = dir.join("en-GB.json");
+        fs::write(&file, r#"{"greeting": "Hello"}"#)?;
+        let (mut localizer, config) = Localizer::with_config(&dir, "json")?;
+
+        fs::write(&file, r#"{"greeting": "Hi"}"#)?;
+        File::options().write(true).open(&file)?.set_modified(SystemTime::now() + Duration::from_secs(60))?;
+        let pending = Arc::new(Mutex::new(vec![Ok(file.clone())]));
+        localizer.watch_state = Some(WatchState { pending, config: config.clone() });
+        assert_eq!(localizer.get_text_by_key("greeting", "en-GB", "en-GB")?, "Hi");
+
+        localizer.rescan_languages(&config)?;
+        assert!(localizer.supported_languages_cache.contains_key("en-GB"));
+
+        fs::remove_file(&file)?;
+        let pending = Arc::new(Mutex::new(vec![Ok(file)]));
+        localizer.watch_state = Some(WatchState { pending, config: config.clone() });
+        assert!(localizer.get_text_by_key("greeting", "en-GB", "en-GB").is_err());
+        assert!(!localizer.scanned_stamps.contains_key("en-GB"));
+        Ok(())
+    }
+}